@@ -0,0 +1,603 @@
+//! The attributes html elements accept, used to check the attributes in templates and for the
+//! typed accessors of the element types. Only html elements are checked, svg and mathml have
+//! too many attributes to list here.
+
+/// attributes every html element accepts
+static GLOBAL_ATTRIBUTES: &[&str] = &[
+    "accesskey",
+    "autocapitalize",
+    "autofocus",
+    "class",
+    "contenteditable",
+    "dir",
+    "draggable",
+    "enterkeyhint",
+    "hidden",
+    "id",
+    "inert",
+    "inputmode",
+    "is",
+    "itemid",
+    "itemprop",
+    "itemref",
+    "itemscope",
+    "itemtype",
+    "lang",
+    "nonce",
+    "part",
+    "popover",
+    "role",
+    "slot",
+    "spellcheck",
+    "style",
+    "tabindex",
+    "title",
+    "translate",
+    "writingsuggestions",
+];
+
+/// how the typed accessors of an attribute read and write it
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Accessor {
+    /// a string
+    Text,
+    /// anything that can be turned into a string, for attributes like `step` that can be a
+    /// number or `any`
+    Display,
+    /// an attribute that is on when it is present
+    Bool,
+    /// a number of the rust type
+    Number(&'static str),
+    /// a property that is set instead of the attribute, so setting it can't fail: the current
+    /// `value` of an input, or `disabled` that also re-enables a control the user changed.
+    /// `bool` or `String`, on the server the attribute is used instead.
+    Property(&'static str),
+}
+
+use Accessor::*;
+
+/// the attributes only some elements accept, and how the typed accessors of each read and
+/// write it
+static ELEMENT_ATTRIBUTES: &[(&str, &[(&str, Accessor)])] = &[
+    (
+        "html",
+        &[("manifest", Text), ("version", Text), ("xmlns", Text)],
+    ),
+    ("base", &[("href", Text), ("target", Text)]),
+    (
+        "link",
+        &[
+            ("as", Text),
+            ("blocking", Text),
+            ("color", Text),
+            ("crossorigin", Text),
+            ("disabled", Property("bool")),
+            ("fetchpriority", Text),
+            ("href", Text),
+            ("hreflang", Text),
+            ("imagesizes", Text),
+            ("imagesrcset", Text),
+            ("integrity", Text),
+            ("media", Text),
+            ("referrerpolicy", Text),
+            ("rel", Text),
+            ("sizes", Text),
+            ("type", Text),
+        ],
+    ),
+    (
+        "meta",
+        &[
+            ("charset", Text),
+            ("content", Text),
+            ("http-equiv", Text),
+            ("media", Text),
+            ("name", Text),
+        ],
+    ),
+    ("style", &[("blocking", Text), ("media", Text)]),
+    ("blockquote", &[("cite", Text)]),
+    ("q", &[("cite", Text)]),
+    (
+        "ol",
+        &[("reversed", Bool), ("start", Number("i32")), ("type", Text)],
+    ),
+    ("li", &[("value", Number("i32"))]),
+    (
+        "a",
+        &[
+            ("download", Text),
+            ("href", Text),
+            ("hreflang", Text),
+            ("ping", Text),
+            ("referrerpolicy", Text),
+            ("rel", Text),
+            ("target", Text),
+            ("type", Text),
+        ],
+    ),
+    ("data", &[("value", Text)]),
+    ("time", &[("datetime", Text)]),
+    ("ins", &[("cite", Text), ("datetime", Text)]),
+    ("del", &[("cite", Text), ("datetime", Text)]),
+    (
+        "source",
+        &[
+            ("height", Number("u32")),
+            ("media", Text),
+            ("sizes", Text),
+            ("src", Text),
+            ("srcset", Text),
+            ("type", Text),
+            ("width", Number("u32")),
+        ],
+    ),
+    (
+        "img",
+        &[
+            ("alt", Text),
+            ("crossorigin", Text),
+            ("decoding", Text),
+            ("fetchpriority", Text),
+            ("height", Number("u32")),
+            ("ismap", Bool),
+            ("loading", Text),
+            ("referrerpolicy", Text),
+            ("sizes", Text),
+            ("src", Text),
+            ("srcset", Text),
+            ("usemap", Text),
+            ("width", Number("u32")),
+        ],
+    ),
+    (
+        "iframe",
+        &[
+            ("allow", Text),
+            ("allowfullscreen", Bool),
+            ("height", Number("u32")),
+            ("loading", Text),
+            ("name", Text),
+            ("referrerpolicy", Text),
+            ("sandbox", Text),
+            ("src", Text),
+            ("srcdoc", Text),
+            ("width", Number("u32")),
+        ],
+    ),
+    (
+        "embed",
+        &[
+            ("height", Number("u32")),
+            ("src", Text),
+            ("type", Text),
+            ("width", Number("u32")),
+        ],
+    ),
+    (
+        "object",
+        &[
+            ("data", Text),
+            ("form", Text),
+            ("height", Number("u32")),
+            ("name", Text),
+            ("type", Text),
+            ("width", Number("u32")),
+        ],
+    ),
+    (
+        "video",
+        &[
+            ("autoplay", Bool),
+            ("controls", Bool),
+            ("crossorigin", Text),
+            ("height", Number("u32")),
+            ("loop", Bool),
+            ("muted", Bool),
+            ("playsinline", Bool),
+            ("poster", Text),
+            ("preload", Text),
+            ("src", Text),
+            ("width", Number("u32")),
+        ],
+    ),
+    (
+        "audio",
+        &[
+            ("autoplay", Bool),
+            ("controls", Bool),
+            ("crossorigin", Text),
+            ("loop", Bool),
+            ("muted", Bool),
+            ("preload", Text),
+            ("src", Text),
+        ],
+    ),
+    (
+        "track",
+        &[
+            ("default", Bool),
+            ("kind", Text),
+            ("label", Text),
+            ("src", Text),
+            ("srclang", Text),
+        ],
+    ),
+    ("map", &[("name", Text)]),
+    (
+        "area",
+        &[
+            ("alt", Text),
+            ("coords", Text),
+            ("download", Text),
+            ("href", Text),
+            ("ping", Text),
+            ("referrerpolicy", Text),
+            ("rel", Text),
+            ("shape", Text),
+            ("target", Text),
+        ],
+    ),
+    ("colgroup", &[("span", Number("u32"))]),
+    ("col", &[("span", Number("u32"))]),
+    (
+        "td",
+        &[
+            ("colspan", Number("u32")),
+            ("headers", Text),
+            ("rowspan", Number("u32")),
+        ],
+    ),
+    (
+        "th",
+        &[
+            ("abbr", Text),
+            ("colspan", Number("u32")),
+            ("headers", Text),
+            ("rowspan", Number("u32")),
+            ("scope", Text),
+        ],
+    ),
+    (
+        "form",
+        &[
+            ("accept-charset", Text),
+            ("action", Text),
+            ("autocomplete", Text),
+            ("enctype", Text),
+            ("method", Text),
+            ("name", Text),
+            ("novalidate", Bool),
+            ("rel", Text),
+            ("target", Text),
+        ],
+    ),
+    ("label", &[("for", Text)]),
+    (
+        "input",
+        &[
+            ("accept", Text),
+            ("alt", Text),
+            ("autocomplete", Text),
+            ("checked", Property("bool")),
+            ("dirname", Text),
+            ("disabled", Property("bool")),
+            ("form", Text),
+            ("formaction", Text),
+            ("formenctype", Text),
+            ("formmethod", Text),
+            ("formnovalidate", Bool),
+            ("formtarget", Text),
+            ("height", Number("u32")),
+            ("list", Text),
+            ("max", Property("String")),
+            ("maxlength", Number("u32")),
+            ("min", Property("String")),
+            ("minlength", Number("u32")),
+            ("multiple", Bool),
+            ("name", Text),
+            ("pattern", Text),
+            ("placeholder", Text),
+            ("popovertarget", Text),
+            ("popovertargetaction", Text),
+            ("readonly", Bool),
+            ("required", Bool),
+            ("size", Number("u32")),
+            ("src", Text),
+            ("step", Display),
+            ("type", Text),
+            ("value", Property("String")),
+            ("width", Number("u32")),
+        ],
+    ),
+    (
+        "button",
+        &[
+            ("disabled", Property("bool")),
+            ("form", Text),
+            ("formaction", Text),
+            ("formenctype", Text),
+            ("formmethod", Text),
+            ("formnovalidate", Bool),
+            ("formtarget", Text),
+            ("name", Text),
+            ("popovertarget", Text),
+            ("popovertargetaction", Text),
+            ("type", Text),
+            ("value", Text),
+        ],
+    ),
+    (
+        "select",
+        &[
+            ("autocomplete", Text),
+            ("disabled", Property("bool")),
+            ("form", Text),
+            ("multiple", Bool),
+            ("name", Text),
+            ("required", Bool),
+            ("size", Number("u32")),
+        ],
+    ),
+    (
+        "optgroup",
+        &[("disabled", Property("bool")), ("label", Text)],
+    ),
+    (
+        "option",
+        &[
+            ("disabled", Property("bool")),
+            ("label", Text),
+            ("selected", Property("bool")),
+            ("value", Text),
+        ],
+    ),
+    (
+        "textarea",
+        &[
+            ("autocomplete", Text),
+            ("cols", Number("u32")),
+            ("dirname", Text),
+            ("disabled", Property("bool")),
+            ("form", Text),
+            ("maxlength", Number("u32")),
+            ("minlength", Number("u32")),
+            ("name", Text),
+            ("placeholder", Text),
+            ("readonly", Bool),
+            ("required", Bool),
+            ("rows", Number("u32")),
+            ("wrap", Text),
+        ],
+    ),
+    ("output", &[("for", Text), ("form", Text), ("name", Text)]),
+    (
+        "progress",
+        &[("max", Number("f64")), ("value", Number("f64"))],
+    ),
+    (
+        "meter",
+        &[
+            ("high", Number("f64")),
+            ("low", Number("f64")),
+            ("max", Number("f64")),
+            ("min", Number("f64")),
+            ("optimum", Number("f64")),
+            ("value", Number("f64")),
+        ],
+    ),
+    (
+        "fieldset",
+        &[
+            ("disabled", Property("bool")),
+            ("form", Text),
+            ("name", Text),
+        ],
+    ),
+    ("details", &[("name", Text), ("open", Bool)]),
+    ("dialog", &[("open", Bool)]),
+    (
+        "script",
+        &[
+            ("async", Bool),
+            ("blocking", Text),
+            ("crossorigin", Text),
+            ("defer", Bool),
+            ("fetchpriority", Text),
+            ("integrity", Text),
+            ("nomodule", Bool),
+            ("referrerpolicy", Text),
+            ("src", Text),
+            ("type", Text),
+        ],
+    ),
+    (
+        "template",
+        &[
+            ("shadowrootclonable", Bool),
+            ("shadowrootdelegatesfocus", Bool),
+            ("shadowrootmode", Text),
+            ("shadowrootserializable", Bool),
+        ],
+    ),
+    ("slot", &[("name", Text)]),
+    (
+        "canvas",
+        &[("height", Number("u32")), ("width", Number("u32"))],
+    ),
+];
+
+/// attributes that only accept some values, for an element or `*` for every element. the
+/// values are compared without case.
+static ENUMERATED: &[(&str, &str, &[&str])] = &[
+    (
+        "*",
+        "autocapitalize",
+        &["off", "none", "on", "sentences", "words", "characters"],
+    ),
+    (
+        "*",
+        "contenteditable",
+        &["", "true", "false", "plaintext-only"],
+    ),
+    ("*", "dir", &["ltr", "rtl", "auto"]),
+    ("*", "draggable", &["true", "false"]),
+    (
+        "*",
+        "enterkeyhint",
+        &["enter", "done", "go", "next", "previous", "search", "send"],
+    ),
+    ("*", "hidden", &["", "hidden", "until-found"]),
+    (
+        "*",
+        "inputmode",
+        &[
+            "none", "text", "decimal", "numeric", "tel", "search", "email", "url",
+        ],
+    ),
+    ("*", "popover", &["", "auto", "manual"]),
+    ("*", "spellcheck", &["", "true", "false"]),
+    ("*", "translate", &["", "yes", "no"]),
+    (
+        "input",
+        "type",
+        &[
+            "button",
+            "checkbox",
+            "color",
+            "date",
+            "datetime-local",
+            "email",
+            "file",
+            "hidden",
+            "image",
+            "month",
+            "number",
+            "password",
+            "radio",
+            "range",
+            "reset",
+            "search",
+            "submit",
+            "tel",
+            "text",
+            "time",
+            "url",
+            "week",
+        ],
+    ),
+    ("button", "type", &["submit", "reset", "button"]),
+    ("form", "method", &["get", "post", "dialog"]),
+    (
+        "form",
+        "enctype",
+        &[
+            "application/x-www-form-urlencoded",
+            "multipart/form-data",
+            "text/plain",
+        ],
+    ),
+    ("ol", "type", &["1", "a", "i"]),
+    ("th", "scope", &["row", "col", "rowgroup", "colgroup"]),
+    ("img", "loading", &["lazy", "eager"]),
+    ("iframe", "loading", &["lazy", "eager"]),
+    ("img", "decoding", &["sync", "async", "auto"]),
+    (
+        "track",
+        "kind",
+        &[
+            "subtitles",
+            "captions",
+            "descriptions",
+            "chapters",
+            "metadata",
+        ],
+    ),
+    ("audio", "preload", &["", "none", "metadata", "auto"]),
+    ("video", "preload", &["", "none", "metadata", "auto"]),
+    ("textarea", "wrap", &["soft", "hard"]),
+];
+
+/// attributes every element accepts that are not a fixed list
+fn is_open_attribute(attribute: &str) -> bool {
+    attribute.starts_with("data-")
+        || attribute.starts_with("aria-")
+        // inline event handlers, `onclick` and the like
+        || (attribute.starts_with("on") && attribute.len() > 2)
+}
+
+fn known_attributes(element: &str) -> impl Iterator<Item = &'static str> + '_ {
+    let specific = ELEMENT_ATTRIBUTES
+        .iter()
+        .filter(move |(e, _)| *e == element)
+        .flat_map(|(_, attributes)| attributes.iter().map(|(a, _)| a));
+    GLOBAL_ATTRIBUTES.iter().chain(specific).copied()
+}
+
+/// checks `attribute` on the html element `element`, `value` is `None` when it is set by an
+/// expression. the problem is returned as a message.
+pub(crate) fn check(element: &str, attribute: &str, value: Option<&str>) -> Result<(), String> {
+    let element = element.to_lowercase();
+    let attribute = attribute.to_lowercase();
+    if !is_open_attribute(&attribute) && !known_attributes(&element).any(|a| a == attribute) {
+        let mut message = format!("unknown attribute `{}` on `<{}>`", attribute, element);
+        if let Some(similar) = known_attributes(&element)
+            .map(|a| (distance(a, &attribute), a))
+            .filter(|(d, _)| *d <= 2)
+            .min()
+        {
+            message.push_str(&format!(", did you mean `{}`?", similar.1));
+        }
+        return Err(message);
+    }
+    let value = match value {
+        Some(value) => value,
+        None => return Ok(()),
+    };
+    let allowed = ENUMERATED
+        .iter()
+        .find(|(e, a, _)| (*e == "*" || *e == element) && *a == attribute);
+    match allowed {
+        Some((_, _, values)) if !values.iter().any(|v| v.eq_ignore_ascii_case(value)) => {
+            let values: Vec<String> = values.iter().map(|v| format!("{:?}", v)).collect();
+            Err(format!(
+                "invalid value {:?} for `{}` on `<{}>`, expected one of {}",
+                value,
+                attribute,
+                element,
+                values.join(", ")
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// the number of single character edits to change `a` into `b`
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                previous
+            } else {
+                1 + previous.min(row[j]).min(row[j + 1])
+            };
+            previous = current;
+        }
+    }
+    row[b.len()]
+}
+
+/// the attributes of the html element `element` that get typed accessors
+pub(crate) fn accessors(
+    element: &str,
+) -> impl Iterator<Item = &'static (&'static str, Accessor)> + '_ {
+    ELEMENT_ATTRIBUTES
+        .iter()
+        .filter(move |(e, _)| *e == element)
+        .flat_map(|(_, attributes)| attributes.iter())
+}
@@ -0,0 +1,129 @@
+//! `#[derive(WeForm)]`, maps the fields of a struct to the inputs of a view with the same
+//! `we_field` name.
+//!
+//! ```ignore
+//! #[derive(Debug, Clone, Default, WeForm)]
+//! #[we_form(view = "SignupView", validate = "Self::check")]
+//! struct Signup {
+//!     #[we_form(validate = "not_empty")]
+//!     name: String,
+//!     #[we_form(field = "accept")]
+//!     terms: bool,
+//! }
+//! ```
+
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{DeriveInput, Ident};
+
+use crate::options;
+
+/// a form field and the view field of its input
+struct Field {
+    ident: Ident,
+    name: String,
+    input: Ident,
+    validators: Vec<syn::Path>,
+}
+
+pub(crate) fn derive(ast: DeriveInput) -> syn::Result<TokenStream> {
+    let ident = &ast.ident;
+    let fields = match &ast.data {
+        syn::Data::Struct(syn::DataStruct {
+            fields: syn::Fields::Named(fields),
+            ..
+        }) => fields,
+        _ => {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`WeForm` can only be derived for structs with named fields",
+            ))
+        }
+    };
+
+    let mut view = None;
+    let mut validators = Vec::new();
+    for (key, value) in options::parse(&ast.attrs, "we_form", &["view", "validate"], &[])? {
+        match key.to_string().as_str() {
+            "view" => view = Some(value.parse::<syn::Type>()?),
+            _ => validators.push(value.parse::<syn::Path>()?),
+        }
+    }
+    let view = view.ok_or_else(|| {
+        syn::Error::new(
+            Span::call_site(),
+            "the view with the inputs is missing, add `#[we_form(view = \"View\")]`",
+        )
+    })?;
+
+    let mut form_fields = Vec::new();
+    'fields: for field in fields.named.iter() {
+        let ident = field.ident.clone().expect("named field");
+        let mut form_field = Field {
+            name: ident.to_string(),
+            input: ident.clone(),
+            ident,
+            validators: Vec::new(),
+        };
+        let keys = &["field", "validate"];
+        for (key, value) in options::parse(&field.attrs, "we_form", keys, &["skip"])? {
+            match key.to_string().as_str() {
+                "skip" => continue 'fields,
+                "field" => form_field.input = value.parse()?,
+                _ => form_field.validators.push(value.parse()?),
+            }
+        }
+        form_fields.push(form_field);
+    }
+
+    let names: Vec<&str> = form_fields.iter().map(|f| f.name.as_str()).collect();
+    // errors about a missing or wrong input point at the field
+    let inputs: Vec<TokenStream> = form_fields
+        .iter()
+        .map(|f| {
+            let input = &f.input;
+            quote_spanned! { input.span()=> &view.#input }
+        })
+        .collect();
+    let field_idents: Vec<&Ident> = form_fields.iter().map(|f| &f.ident).collect();
+    let field_validators = form_fields.iter().flat_map(|f| {
+        let (ident, name) = (&f.ident, &f.name);
+        f.validators.iter().map(move |validator| {
+            quote! {
+                if let Err(e) = #validator(&self.#ident) {
+                    errors.add(#name, e);
+                }
+            }
+        })
+    });
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics webelements::form::FormModel for #ident #ty_generics #where_clause {
+            type View = #view;
+
+            const FIELDS: &'static [&'static str] = &[#( #names ),*];
+
+            fn controls(view: &Self::View) -> Vec<(&'static str, &dyn webelements::form::FormControl)> {
+                vec![#( (#names, #inputs) ),*]
+            }
+
+            fn read(&mut self, view: &Self::View, field: &str) -> webelements::Result<()> {
+                match field {
+                    #( #names => self.#field_idents = webelements::form::FormValue::read(#inputs)?, )*
+                    _ => return Err(webelements::Error::NotFound),
+                }
+                Ok(())
+            }
+
+            fn write(&self, view: &Self::View) {
+                #( webelements::form::FormValue::write(&self.#field_idents, #inputs); )*
+            }
+
+            fn validate(&self, errors: &mut webelements::form::FormErrors) {
+                #( #field_validators )*
+                #( #validators(self, errors); )*
+            }
+        }
+    })
+}
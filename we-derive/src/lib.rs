@@ -1,90 +1,600 @@
-use std::str::FromStr;
+use proc_macro2::{Ident, Span, TokenStream, TokenTree};
 
-use html_parser::{Dom, Node};
-use proc_macro2::{Ident, LineColumn, TokenStream, TokenTree};
+use quote::{format_ident, quote};
+use syn::{
+    parse::{ParseStream, Parser},
+    parse_macro_input, DeriveInput, Token,
+};
 
-use quote::{format_ident, quote, quote_spanned};
-use syn::{parse::Parser, parse_macro_input, DeriveInput};
+mod attributes;
+mod form;
+mod options;
+mod template;
+mod web_element;
+
+use template::{Element, Node, Segment, Template, Value};
 
 struct DomParsed {
     fields: Vec<syn::Field>,
     root_type: Option<syn::Path>,
     root_is_element: bool,
+    props: TokenStream,
     build: TokenStream,
     errors: TokenStream,
 }
 
+/// how an element is added to its parent
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ElementKind {
+    /// the code will evaluate to the element
+    Single,
+    /// the element is repeated `n` times, the code will evaluate to a `Vec` of elements
+    Repeat(usize),
+    /// the element is rendered for each item with `we_for`, the code adds the list to `_parent`
+    List,
+    /// the element is rendered with `we_if` or `we_else`, the code adds it to `_parent`
+    Conditional,
+    /// a text node, the code adds it to `_parent`
+    Text,
+}
+
+/// generated code for a single element in the template
+struct ElementTokens {
+    kind: ElementKind,
+    /// code that creates the element and its children
+    build: TokenStream,
+    /// code that takes the element and its children from an existing dom
+    hydrate: TokenStream,
+}
+
+impl ElementTokens {
+    fn error(error: TokenStream) -> Vec<ElementTokens> {
+        vec![ElementTokens {
+            kind: ElementKind::Single,
+            build: error,
+            // the error is already reported by the build code
+            hydrate: quote! { unreachable!() },
+        }]
+    }
+}
+
+/// an event handler bound in the template with `we_on:event="handler"`
+struct EventBinding {
+    /// variable that collects the elements the handler is bound to
+    targets: Ident,
+    event: String,
+    handler: TokenStream,
+}
+
+/// the first and last span of a part of the template, errors are reported over the whole range
+#[derive(Debug, Clone, Copy)]
+struct SpanRange {
+    start: Span,
+    end: Span,
+}
+
+impl SpanRange {
+    fn new(start: Span, end: Span) -> SpanRange {
+        SpanRange { start, end }
+    }
+
+    fn syn_error(&self, message: &str) -> syn::Error {
+        // `new_spanned` takes the span of the first and the last token
+        let tokens: TokenStream = vec![
+            TokenTree::Ident(Ident::new("start", self.start)),
+            TokenTree::Ident(Ident::new("end", self.end)),
+        ]
+        .into_iter()
+        .collect();
+        syn::Error::new_spanned(tokens, message)
+    }
+
+    fn error(&self, message: &str) -> TokenStream {
+        self.syn_error(message).to_compile_error()
+    }
+
+    fn warning(&self, message: &str) -> TokenStream {
+        // proc macros can't emit warnings on stable, the use of a deprecated constant gives one
+        let used = Ident::new("invalid_attribute", self.start);
+        quote! {
+            {
+                #[deprecated(note = #message)]
+                #[allow(non_upper_case_globals)]
+                const invalid_attribute: () = ();
+                #used
+            };
+        }
+    }
+}
+
+/// where an element and its attributes are in the template
+#[derive(Debug, Clone)]
+struct TagSpans {
+    /// the `<` and the name of the element
+    tag: SpanRange,
+    /// the name and value of each attribute
+    attributes: Vec<(String, SpanRange)>,
+}
+
+impl TagSpans {
+    fn error(&self, message: &str) -> TokenStream {
+        self.tag.error(message)
+    }
+
+    /// an error on the attribute `key`, or on the tag if the attribute was not found
+    fn attr_error(&self, key: &str, message: &str) -> TokenStream {
+        self.attributes
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, span)| span)
+            .unwrap_or(&self.tag)
+            .error(message)
+    }
+}
+
 static ELEM_INPUT: &[(&str, &str, &str)] = &[
+    ("html", "Html", "HtmlHtmlElement"),
+    ("head", "Head", "HtmlHeadElement"),
+    ("title", "Title", "HtmlTitleElement"),
+    ("base", "BaseUrl", "HtmlBaseElement"),
+    ("link", "Link", "HtmlLinkElement"),
+    ("meta", "Meta", "HtmlMetaElement"),
+    ("style", "Style", "HtmlStyleElement"),
     ("body", "Base", "HtmlElement"),
-    ("div", "Div", "HtmlElement"),
-    ("p", "Paragraph", "HtmlElement"),
+    ("article", "Article", "HtmlElement"),
+    ("section", "Section", "HtmlElement"),
+    ("nav", "Nav", "HtmlElement"),
+    ("aside", "Aside", "HtmlElement"),
+    ("h1", "H1", "HtmlHeadingElement"),
+    ("h2", "H2", "HtmlHeadingElement"),
+    ("h3", "H3", "HtmlHeadingElement"),
+    ("h4", "H4", "HtmlHeadingElement"),
+    ("h5", "H5", "HtmlHeadingElement"),
+    ("h6", "H6", "HtmlHeadingElement"),
+    ("hgroup", "Hgroup", "HtmlElement"),
+    ("header", "Header", "HtmlElement"),
+    ("footer", "Footer", "HtmlElement"),
+    ("address", "Address", "HtmlElement"),
+    ("p", "Paragraph", "HtmlParagraphElement"),
+    ("hr", "Hr", "HtmlHrElement"),
+    ("pre", "Pre", "HtmlPreElement"),
+    ("blockquote", "Blockquote", "HtmlQuoteElement"),
+    ("ol", "Ol", "HtmlOListElement"),
+    ("ul", "Ul", "HtmlUListElement"),
+    ("menu", "Menu", "HtmlMenuElement"),
+    ("li", "Li", "HtmlLiElement"),
+    ("dl", "Dl", "HtmlDListElement"),
+    ("dt", "Dt", "HtmlElement"),
+    ("dd", "Dd", "HtmlElement"),
+    ("figure", "Figure", "HtmlElement"),
+    ("figcaption", "Figcaption", "HtmlElement"),
+    ("main", "Main", "HtmlElement"),
+    ("search", "Search", "HtmlElement"),
+    ("div", "Div", "HtmlDivElement"),
+    ("a", "Anchor", "HtmlAnchorElement"),
+    ("em", "Em", "HtmlElement"),
+    ("strong", "Strong", "HtmlElement"),
+    ("small", "Small", "HtmlElement"),
+    ("s", "Strikethrough", "HtmlElement"),
+    ("cite", "Cite", "HtmlElement"),
+    ("q", "Quote", "HtmlQuoteElement"),
+    ("dfn", "Dfn", "HtmlElement"),
+    ("abbr", "Abbr", "HtmlElement"),
+    ("ruby", "Ruby", "HtmlElement"),
+    ("rt", "Rt", "HtmlElement"),
+    ("rp", "Rp", "HtmlElement"),
+    ("data", "Data", "HtmlDataElement"),
+    ("time", "Time", "HtmlTimeElement"),
+    ("code", "Code", "HtmlElement"),
+    ("var", "Var", "HtmlElement"),
+    ("samp", "Samp", "HtmlElement"),
+    ("kbd", "Kbd", "HtmlElement"),
+    ("sub", "Sub", "HtmlElement"),
+    ("sup", "Sup", "HtmlElement"),
+    ("i", "Italic", "HtmlElement"),
+    ("b", "Bold", "HtmlElement"),
+    ("u", "Underline", "HtmlElement"),
+    ("mark", "Mark", "HtmlElement"),
+    ("bdi", "Bdi", "HtmlElement"),
+    ("bdo", "Bdo", "HtmlElement"),
     ("span", "Span", "HtmlSpanElement"),
+    ("br", "Br", "HtmlBrElement"),
+    ("wbr", "Wbr", "HtmlElement"),
+    ("ins", "Ins", "HtmlModElement"),
+    ("del", "Del", "HtmlModElement"),
+    ("picture", "Picture", "HtmlPictureElement"),
+    ("source", "Source", "HtmlSourceElement"),
+    ("img", "Img", "HtmlImageElement"),
+    ("iframe", "Iframe", "HtmlIFrameElement"),
+    ("embed", "Embed", "HtmlEmbedElement"),
+    ("object", "Object", "HtmlObjectElement"),
+    ("video", "Video", "HtmlVideoElement"),
+    ("audio", "Audio", "HtmlAudioElement"),
+    ("track", "Track", "HtmlTrackElement"),
+    ("map", "Map", "HtmlMapElement"),
+    ("area", "Area", "HtmlAreaElement"),
+    ("table", "Table", "HtmlTableElement"),
+    ("caption", "Caption", "HtmlTableCaptionElement"),
+    ("colgroup", "Colgroup", "HtmlTableColElement"),
+    ("col", "Col", "HtmlTableColElement"),
+    ("tbody", "Tbody", "HtmlTableSectionElement"),
+    ("thead", "Thead", "HtmlTableSectionElement"),
+    ("tfoot", "Tfoot", "HtmlTableSectionElement"),
+    ("tr", "Tr", "HtmlTableRowElement"),
+    ("td", "Td", "HtmlTableCellElement"),
+    ("th", "Th", "HtmlTableCellElement"),
+    ("form", "Form", "HtmlFormElement"),
+    ("label", "Label", "HtmlLabelElement"),
     ("input", "Input", "HtmlInputElement"),
     ("button", "Button", "HtmlButtonElement"),
+    ("select", "Select", "HtmlSelectElement"),
+    ("datalist", "Datalist", "HtmlDataListElement"),
+    ("optgroup", "Optgroup", "HtmlOptGroupElement"),
+    ("option", "SelectOption", "HtmlOptionElement"),
+    ("textarea", "Textarea", "HtmlTextAreaElement"),
+    ("output", "Output", "HtmlOutputElement"),
+    ("progress", "Progress", "HtmlProgressElement"),
+    ("meter", "Meter", "HtmlMeterElement"),
+    ("fieldset", "Fieldset", "HtmlFieldSetElement"),
+    ("legend", "Legend", "HtmlLegendElement"),
+    ("details", "Details", "HtmlDetailsElement"),
+    ("summary", "Summary", "HtmlElement"),
+    ("dialog", "Dialog", "HtmlDialogElement"),
+    ("script", "Script", "HtmlScriptElement"),
+    ("noscript", "Noscript", "HtmlElement"),
+    ("template", "Template", "HtmlTemplateElement"),
+    ("slot", "Slot", "HtmlSlotElement"),
+    ("canvas", "Canvas", "HtmlCanvasElement"),
 ];
 
+static SVG_INPUT: &[(&str, &str, &str)] = &[
+    ("a", "A", "SvgaElement"),
+    ("animate", "Animate", "SvgAnimateElement"),
+    ("animateMotion", "AnimateMotion", "SvgAnimateMotionElement"),
+    (
+        "animateTransform",
+        "AnimateTransform",
+        "SvgAnimateTransformElement",
+    ),
+    ("circle", "Circle", "SvgCircleElement"),
+    ("clipPath", "ClipPath", "SvgClipPathElement"),
+    ("defs", "Defs", "SvgDefsElement"),
+    ("desc", "Desc", "SvgDescElement"),
+    ("ellipse", "Ellipse", "SvgEllipseElement"),
+    ("feBlend", "FeBlend", "SvgfeBlendElement"),
+    ("feColorMatrix", "FeColorMatrix", "SvgfeColorMatrixElement"),
+    (
+        "feComponentTransfer",
+        "FeComponentTransfer",
+        "SvgfeComponentTransferElement",
+    ),
+    ("feComposite", "FeComposite", "SvgfeCompositeElement"),
+    (
+        "feConvolveMatrix",
+        "FeConvolveMatrix",
+        "SvgfeConvolveMatrixElement",
+    ),
+    (
+        "feDiffuseLighting",
+        "FeDiffuseLighting",
+        "SvgfeDiffuseLightingElement",
+    ),
+    (
+        "feDisplacementMap",
+        "FeDisplacementMap",
+        "SvgfeDisplacementMapElement",
+    ),
+    (
+        "feDistantLight",
+        "FeDistantLight",
+        "SvgfeDistantLightElement",
+    ),
+    ("feDropShadow", "FeDropShadow", "SvgfeDropShadowElement"),
+    ("feFlood", "FeFlood", "SvgfeFloodElement"),
+    ("feFuncA", "FeFuncA", "SvgfeFuncAElement"),
+    ("feFuncB", "FeFuncB", "SvgfeFuncBElement"),
+    ("feFuncG", "FeFuncG", "SvgfeFuncGElement"),
+    ("feFuncR", "FeFuncR", "SvgfeFuncRElement"),
+    (
+        "feGaussianBlur",
+        "FeGaussianBlur",
+        "SvgfeGaussianBlurElement",
+    ),
+    ("feImage", "FeImage", "SvgfeImageElement"),
+    ("feMerge", "FeMerge", "SvgfeMergeElement"),
+    ("feMergeNode", "FeMergeNode", "SvgfeMergeNodeElement"),
+    ("feMorphology", "FeMorphology", "SvgfeMorphologyElement"),
+    ("feOffset", "FeOffset", "SvgfeOffsetElement"),
+    ("fePointLight", "FePointLight", "SvgfePointLightElement"),
+    (
+        "feSpecularLighting",
+        "FeSpecularLighting",
+        "SvgfeSpecularLightingElement",
+    ),
+    ("feSpotLight", "FeSpotLight", "SvgfeSpotLightElement"),
+    ("feTile", "FeTile", "SvgfeTileElement"),
+    ("feTurbulence", "FeTurbulence", "SvgfeTurbulenceElement"),
+    ("filter", "Filter", "SvgFilterElement"),
+    ("foreignObject", "ForeignObject", "SvgForeignObjectElement"),
+    ("g", "G", "SvggElement"),
+    ("image", "Image", "SvgImageElement"),
+    ("line", "Line", "SvgLineElement"),
+    (
+        "linearGradient",
+        "LinearGradient",
+        "SvgLinearGradientElement",
+    ),
+    ("marker", "Marker", "SvgMarkerElement"),
+    ("mask", "Mask", "SvgMaskElement"),
+    ("metadata", "Metadata", "SvgMetadataElement"),
+    ("mpath", "Mpath", "SvgmPathElement"),
+    ("path", "Path", "SvgPathElement"),
+    ("pattern", "Pattern", "SvgPatternElement"),
+    ("polygon", "Polygon", "SvgPolygonElement"),
+    ("polyline", "Polyline", "SvgPolylineElement"),
+    (
+        "radialGradient",
+        "RadialGradient",
+        "SvgRadialGradientElement",
+    ),
+    ("rect", "Rect", "SvgRectElement"),
+    ("script", "Script", "SvgScriptElement"),
+    ("set", "Set", "SvgSetElement"),
+    ("stop", "Stop", "SvgStopElement"),
+    ("style", "Style", "SvgStyleElement"),
+    ("svg", "Svg", "SvgsvgElement"),
+    ("switch", "Switch", "SvgSwitchElement"),
+    ("symbol", "Symbol", "SvgSymbolElement"),
+    ("text", "Text", "SvgTextElement"),
+    ("textPath", "TextPath", "SvgTextPathElement"),
+    ("title", "Title", "SvgTitleElement"),
+    ("tspan", "Tspan", "SvgtSpanElement"),
+    ("use", "Use", "SvgUseElement"),
+    ("view", "View", "SvgViewElement"),
+];
+
+static MATHML_INPUT: &[(&str, &str, &str)] = &[
+    ("math", "Math", "MathMlElement"),
+    ("annotation", "Annotation", "MathMlElement"),
+    ("annotation-xml", "AnnotationXml", "MathMlElement"),
+    ("maction", "Maction", "MathMlElement"),
+    ("merror", "Merror", "MathMlElement"),
+    ("mfrac", "Mfrac", "MathMlElement"),
+    ("mi", "Mi", "MathMlElement"),
+    ("mmultiscripts", "Mmultiscripts", "MathMlElement"),
+    ("mn", "Mn", "MathMlElement"),
+    ("mo", "Mo", "MathMlElement"),
+    ("mover", "Mover", "MathMlElement"),
+    ("mpadded", "Mpadded", "MathMlElement"),
+    ("mphantom", "Mphantom", "MathMlElement"),
+    ("mprescripts", "Mprescripts", "MathMlElement"),
+    ("mroot", "Mroot", "MathMlElement"),
+    ("mrow", "Mrow", "MathMlElement"),
+    ("ms", "Ms", "MathMlElement"),
+    ("mspace", "Mspace", "MathMlElement"),
+    ("msqrt", "Msqrt", "MathMlElement"),
+    ("mstyle", "Mstyle", "MathMlElement"),
+    ("msub", "Msub", "MathMlElement"),
+    ("msubsup", "Msubsup", "MathMlElement"),
+    ("msup", "Msup", "MathMlElement"),
+    ("mtable", "Mtable", "MathMlElement"),
+    ("mtd", "Mtd", "MathMlElement"),
+    ("mtext", "Mtext", "MathMlElement"),
+    ("mtr", "Mtr", "MathMlElement"),
+    ("munder", "Munder", "MathMlElement"),
+    ("munderover", "Munderover", "MathMlElement"),
+    ("semantics", "Semantics", "MathMlElement"),
+];
+
+/// The namespace an element in the template is created in. `<svg>` and `<math>` switch
+/// the namespace for themselves and all their children, `<foreignObject>` switches back to html.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Namespace {
+    Html,
+    Svg,
+    MathMl,
+}
+
+impl Namespace {
+    fn table(self) -> &'static [(&'static str, &'static str, &'static str)] {
+        match self {
+            Namespace::Html => ELEM_INPUT,
+            Namespace::Svg => SVG_INPUT,
+            Namespace::MathMl => MATHML_INPUT,
+        }
+    }
+
+    fn uri(self) -> Option<&'static str> {
+        match self {
+            Namespace::Html => None,
+            Namespace::Svg => Some("http://www.w3.org/2000/svg"),
+            Namespace::MathMl => Some("http://www.w3.org/1998/Math/MathML"),
+        }
+    }
+
+    fn module(self) -> TokenStream {
+        match self {
+            Namespace::Html => quote! { webelements::elem },
+            Namespace::Svg => quote! { webelements::elem::svg },
+            Namespace::MathMl => quote! { webelements::elem::math },
+        }
+    }
+
+    /// namespace of an element with `name` that is placed in a parent with namespace `self`
+    fn element(self, name: &str) -> Namespace {
+        match (self, name.to_lowercase().as_str()) {
+            (Namespace::Html, "svg") => Namespace::Svg,
+            (Namespace::Html, "math") => Namespace::MathMl,
+            (ns, _) => ns,
+        }
+    }
+
+    /// namespace of the children of an element with `name` that has namespace `self`
+    fn children(self, name: &str) -> Namespace {
+        match (self, name.to_lowercase().as_str()) {
+            (Namespace::Svg, "foreignobject") => Namespace::Html,
+            (ns, _) => ns,
+        }
+    }
+
+    /// path to the element type for the element with `name` in this namespace
+    fn elem_path(self, name: &str) -> Option<syn::Path> {
+        let name = name.to_lowercase();
+        let ident = self.table().iter().find_map(|s| {
+            if name == s.0.to_lowercase() {
+                Some(format_ident!("{}", s.1))
+            } else {
+                None
+            }
+        })?;
+        let module = self.module();
+        syn::parse2::<syn::Path>(quote! { #module::#ident }).ok()
+    }
+}
+
 fn parse_args(args: TokenStream, s_fields: &syn::FieldsNamed) -> DomParsed {
-    let args: Vec<TokenTree> = args.into_iter().collect();
-    let dom = parse_dom(&args);
-    match dom {
-        Ok(dom) => gen_element(dom, s_fields),
+    match Template::parse(args) {
+        Ok(template) => gen_element(&template.nodes, s_fields),
         Err(e) => {
-            let e = e.to_string();
-            let dom_start = args.first().expect("dom has a start").span();
-            let dom_end = args.last().expect("dom has an end").span();
-            let dom_span = dom_start.join(dom_end).expect("creating dom span");
+            let errors = e.to_compile_error();
             DomParsed {
                 fields: Default::default(),
                 root_type: None,
                 root_is_element: true,
+                props: quote! { () },
                 build: quote! {},
-                errors: quote_spanned! {
-                    dom_span => compile_error!(#e)
-                },
+                errors,
             }
         }
     }
 }
 
-fn parse_dom(input: &[TokenTree]) -> html_parser::Result<Dom> {
-    let mut html = String::new();
-    let mut end: Option<LineColumn> = None;
-    let mut offset: Option<usize> = None;
-    for token in input {
-        let span = token.span().start();
-        if offset.is_none() {
-            offset = Some(span.column);
-        }
-        if let Some(end) = end {
-            if span.line > end.line {
-                html.push('\n');
-                html.push_str(
-                    &" ".repeat(
-                        span.column
-                            .saturating_sub(offset.expect("html span cannot underflow")),
-                    ),
-                )
-            } else {
-                html.push_str(&" ".repeat(span.column - end.column))
+/// turns a text or attribute value from the html into an expression of a string, the
+/// `{expr}` segments are replaced by the `Display` value of the expression.
+fn interpolate(segments: &[Segment]) -> TokenStream {
+    let mut text = String::new();
+    let mut format = String::new();
+    let mut args = Vec::new();
+    for segment in segments {
+        match segment {
+            Segment::Text(t) => {
+                text.push_str(t);
+                format.push_str(&t.replace('{', "{{").replace('}', "}}"));
+            }
+            Segment::Expr(expr) => {
+                format.push_str("{}");
+                args.push(expr);
             }
-        } else {
-            html.push_str(
-                &" ".repeat(
-                    span.column
-                        .saturating_sub(offset.expect("html span cannot underflow")),
-                ),
-            )
         }
-        end = Some(token.span().end());
-        html.push_str(&token.to_string());
     }
-    Dom::parse(&html)
+    if args.is_empty() {
+        return quote! { #text };
+    }
+    quote! { format!(#format, #( (#args) ),*) }
+}
+
+/// adds `text` to the end of `segments`, joined with the text segment there is one
+fn push_text(segments: &mut Vec<Segment>, text: &str) {
+    match segments.last_mut() {
+        Some(Segment::Text(last)) => last.push_str(text),
+        _ => segments.push(Segment::Text(text.to_owned())),
+    }
+}
+
+/// expression for a props field set by an attribute on a custom element. an `{expr}` is used
+/// as is, numbers and booleans are literals and anything else is a string.
+fn prop_value(value: Option<&Value>) -> TokenStream {
+    let (text, tokens) = match value {
+        Some(Value::Text(text, tokens)) => (text, tokens),
+        Some(Value::Expr(expr)) => return quote! { (#expr) },
+        None => return quote! { true },
+    };
+    match syn::parse2::<syn::Lit>(tokens.clone()) {
+        Ok(syn::Lit::Int(_)) | Ok(syn::Lit::Float(_)) | Ok(syn::Lit::Bool(_)) => quote! { #tokens },
+        Ok(syn::Lit::Str(lit)) => quote! { Into::into(#lit) },
+        _ => quote! { Into::into(#text) },
+    }
+}
+
+/// the handler of a `we_on` binding, a path to a function or an `{expr}`
+fn handler_expr(value: &Value) -> Option<TokenStream> {
+    match value {
+        Value::Expr(expr) => Some(expr.clone()),
+        text => text
+            .parse::<syn::ExprPath>()
+            .ok()
+            .map(|path| quote! { #path }),
+    }
 }
 
-fn walk_dom(dom: &[Node], refs: &mut Vec<(Ident, syn::Path)>) -> Vec<(bool, TokenStream)> {
+/// the expression of a `we_if` or `we_key` value, an `{expr}` or rust code in a string
+fn expr_value(value: &Value) -> Option<TokenStream> {
+    value.parse::<TokenStream>().ok().filter(|t| !t.is_empty())
+}
+
+/// the pattern and the items of a `we_for` value like `todo in props.todos`
+fn for_in(input: ParseStream) -> syn::Result<(TokenStream, TokenStream)> {
+    let mut pat = TokenStream::new();
+    while !input.peek(Token![in]) {
+        if input.is_empty() {
+            return Err(input.error("expected `in`"));
+        }
+        pat.extend(Some(input.parse::<TokenTree>()?));
+    }
+    input.parse::<Token![in]>()?;
+    Ok((pat, input.parse()?))
+}
+
+/// reports a problem with an attribute as warning, or as error with the `strict-attributes`
+/// feature.
+fn attribute_problem(tag: &TagSpans, key: &str, message: &str) -> TokenStream {
+    let span = tag
+        .attributes
+        .iter()
+        .find(|(k, _)| k == key)
+        .map(|(_, span)| span)
+        .unwrap_or(&tag.tag);
+    if cfg!(feature = "strict-attributes") {
+        span.error(message)
+    } else {
+        span.warning(message)
+    }
+}
+
+fn walk_dom(
+    dom: &[Node],
+    refs: &mut Vec<(Ident, syn::Path)>,
+    events: &mut Vec<EventBinding>,
+    ns: Namespace,
+) -> Vec<ElementTokens> {
     let mut elements = Vec::new();
-    for node in dom {
+    // the condition of the previous element if it had `we_if`, for `we_else`
+    let mut last_if: Option<TokenStream> = None;
+    for (i, node) in dom.iter().enumerate() {
+        if let Node::Text(_) = node {
+            if let Some(text) = node_text(dom, i) {
+                let text = interpolate(&text);
+                elements.push(ElementTokens {
+                    kind: ElementKind::Text,
+                    build: quote! { _parent.append_text(#text)?; },
+                    hydrate: quote! {},
+                });
+            }
+            continue;
+        }
+        if let Node::Element(element @ Element { name, .. }) = node {
+            if name == "we_text" {
+                elements.push(match text_field(element, dom, i, refs) {
+                    Ok(tokens) => tokens,
+                    Err(error) => return ElementTokens::error(error),
+                });
+                continue;
+            }
+        }
         if let Node::Element(element) = node {
+            let ns = ns.element(&element.name);
+            let tag = &element.spans;
+
             // flag for if this element will be a member field in the struct
             let mut is_field = None;
 
@@ -94,214 +604,751 @@ fn walk_dom(dom: &[Node], refs: &mut Vec<(Ident, syn::Path)>) -> Vec<(bool, Toke
             // flag for if this element will be repeated
             let mut is_repeat = None;
 
+            // the pattern and items of a `we_for` list and the key of its items
+            let mut is_for = None;
+            let mut for_key = None;
+
+            // the condition of a `we_if` element, and whether the element has `we_else`
+            let mut is_if = None;
+            let mut is_else = false;
+
             // list of attributes that the element will have. all crate options will be filtered out
             let mut attributes = Vec::new();
 
+            // event bindings on this element, the element is added to the targets of each
+            let mut bindings = Vec::new();
+
+            // signals bound to the text, attributes or classes of this element
+            let mut signals = Vec::new();
+
+            // attributes that are not checked against the attributes the element accepts
+            let mut unchecked = Vec::new();
+            let mut unchecked_all = false;
+
             for (key, value) in element.attributes.iter() {
                 if key == "we_field" {
-                    is_field = value.clone()
+                    match value {
+                        Some(Value::Expr(_)) => {
+                            return ElementTokens::error(
+                                tag.attr_error("we_field", "`we_field` needs a name as value"),
+                            )
+                        }
+                        value => is_field = value.as_ref().and_then(Value::text).map(str::to_owned),
+                    }
                 } else if key == "we_element" {
                     // the custom path will be generated from the elements name
-                    let custom = syn::parse2::<syn::Path>(
-                        TokenStream::from_str(&element.name).expect("custom path name tokenstream"),
-                    )
-                    .expect("custom path tokenstream");
-                    is_custom = Some(custom);
+                    match syn::parse2::<syn::Path>(element.path.clone()) {
+                        Ok(custom) => is_custom = Some(custom),
+                        Err(_) => {
+                            return ElementTokens::error(
+                                tag.error("the name of a `we_element` element must be a path"),
+                            )
+                        }
+                    }
 
                     // the custom element cant have any children because they can't be appended to it.
                     if !element.children.is_empty() {
-                        return vec![(
-                            false,
-                            quote! {
-                                compile_error!("`we_element` element cant have any children")
-                            },
-                        )];
+                        return ElementTokens::error(
+                            tag.error("`we_element` element cant have any children"),
+                        );
                     }
                 } else if key == "we_repeat" {
                     if let Some(n) = value {
-                        if let Ok(n) = n.parse::<i64>() {
+                        if let Some(n) = n.text().and_then(|n| n.parse::<i64>().ok()) {
                             is_repeat = Some(n);
                         } else {
-                            return vec![(
-                                false,
-                                quote! {
-                                    compile_error!("`we_repeat` mut have a positive interger value")
-                                },
-                            )];
+                            return ElementTokens::error(tag.attr_error(
+                                "we_repeat",
+                                "`we_repeat` mut have a positive interger value",
+                            ));
                         }
                     } else {
-                        return vec![(
-                            false,
-                            quote! {
-                                compile_error!("`we_repeat` needs a value")
-                            },
-                        )];
+                        return ElementTokens::error(
+                            tag.attr_error("we_repeat", "`we_repeat` needs a value"),
+                        );
+                    }
+                } else if key == "we_for" {
+                    let parsed = value.as_ref().and_then(|v| v.parse_with(for_in).ok());
+                    match parsed {
+                        Some(parsed) if !parsed.0.is_empty() && !parsed.1.is_empty() => {
+                            is_for = Some(parsed)
+                        }
+                        _ => {
+                            return ElementTokens::error(tag.attr_error(
+                                "we_for",
+                                "`we_for` needs a value like \"item in items\"",
+                            ))
+                        }
+                    }
+                } else if key == "we_key" {
+                    match value.as_ref().and_then(expr_value) {
+                        Some(key) => for_key = Some(key),
+                        None => {
+                            return ElementTokens::error(
+                                tag.attr_error("we_key", "`we_key` needs an expression as value"),
+                            )
+                        }
+                    }
+                } else if key == "we_if" {
+                    match value.as_ref().and_then(expr_value) {
+                        Some(condition) => is_if = Some(condition),
+                        None => {
+                            return ElementTokens::error(
+                                tag.attr_error("we_if", "`we_if` needs a condition as value"),
+                            )
+                        }
+                    }
+                } else if key == "we_else" {
+                    if value.is_some() {
+                        return ElementTokens::error(
+                            tag.attr_error("we_else", "`we_else` can't have a value"),
+                        );
+                    }
+                    is_else = true;
+                } else if let Some(event) = key.strip_prefix("we_on:") {
+                    let handler = match value.as_ref().and_then(handler_expr) {
+                        Some(handler) => handler,
+                        None => {
+                            return ElementTokens::error(
+                                tag.attr_error(key, "`we_on` needs a handler function as value"),
+                            )
+                        }
+                    };
+                    let targets = format_ident!("_ev_{}", events.len());
+                    bindings.push(targets.clone());
+                    events.push(EventBinding {
+                        targets,
+                        event: event.to_owned(),
+                        handler,
+                    });
+                } else if let Some(target) = key.strip_prefix("we_bind:") {
+                    let signal = match value {
+                        Some(Value::Expr(signal)) => signal,
+                        _ => {
+                            return ElementTokens::error(
+                                tag.attr_error(key, "`we_bind` needs a signal `{expr}` as value"),
+                            )
+                        }
+                    };
+                    signals.push(match target.strip_prefix("class:") {
+                        _ if target == "text" => quote! { bind_text(&(#signal)) },
+                        Some(class) => quote! { bind_class(#class, &(#signal)) },
+                        None => quote! { bind_attr(#target, &(#signal))? },
+                    });
+                } else if key == "we_unchecked" {
+                    // without a value none of the attributes are checked
+                    match value {
+                        Some(Value::Text(value, _)) => unchecked.extend(value.split_whitespace()),
+                        Some(Value::Expr(_)) => {
+                            return ElementTokens::error(tag.attr_error(
+                                "we_unchecked",
+                                "`we_unchecked` needs the names of the attributes as value",
+                            ))
+                        }
+                        None => unchecked_all = true,
                     }
+                } else if key == "we_props" {
+                    // the props type of the struct, handled when generating the struct
                 } else {
                     attributes.push((key, value));
                 }
             }
-            let name = &element.name;
-            // find the identifier for the element type in the static list
-            let field = ELEM_INPUT.iter().find_map(|s| {
-                if name.to_lowercase() == s.0 {
-                    Some(format_ident!("{}", s.1))
-                } else {
-                    None
+
+            if is_for.is_some() {
+                if for_key.is_none() {
+                    return ElementTokens::error(
+                        tag.attr_error("we_for", "`we_for` needs a `we_key` to identify the items"),
+                    );
                 }
-            });
+                if is_field.is_some() || is_repeat.is_some() || !bindings.is_empty() {
+                    return ElementTokens::error(tag.attr_error(
+                        "we_for",
+                        "`we_for` can't be combined with `we_field`, `we_repeat` or `we_on`",
+                    ));
+                }
+            } else if for_key.is_some() {
+                return ElementTokens::error(
+                    tag.attr_error("we_key", "`we_key` can only be used together with `we_for`"),
+                );
+            }
+
+            let condition = match (is_if.as_ref(), is_else, last_if.take()) {
+                (Some(_), true, _) => {
+                    return ElementTokens::error(tag.attr_error(
+                        "we_else",
+                        "`we_if` and `we_else` can't be used on the same element",
+                    ))
+                }
+                (Some(condition), false, _) => Some(condition.clone()),
+                (None, true, Some(condition)) => {
+                    Some(quote! { webelements::conditional::Not(&(#condition)) })
+                }
+                (None, true, None) => {
+                    return ElementTokens::error(
+                        tag.attr_error("we_else", "`we_else` must follow an element with `we_if`"),
+                    )
+                }
+                (None, false, _) => None,
+            };
+            last_if = is_if;
+            if condition.is_some()
+                && (is_repeat.is_some() || is_for.is_some() || !bindings.is_empty())
+            {
+                return ElementTokens::error(tag.error(
+                    "`we_if` and `we_else` can't be combined with `we_repeat`, `we_for` or `we_on`",
+                ));
+            }
+
+            // the attributes of html elements are checked, custom elements check their props
+            let mut checks = Vec::new();
+            if is_custom.is_none() && ns == Namespace::Html && !unchecked_all {
+                for (key, value) in attributes.iter() {
+                    if unchecked.contains(&key.as_str()) {
+                        continue;
+                    }
+                    let value = match value {
+                        Some(Value::Expr(_)) => None,
+                        Some(Value::Text(value, _)) => Some(value.as_str()),
+                        None => Some(""),
+                    };
+                    if let Err(message) = attributes::check(&element.name, key, value) {
+                        checks.push(attribute_problem(tag, key, &message));
+                    }
+                }
+            }
+
+            // attributes on a custom element are the fields of its props, only the id is
+            // set on the element itself. classes of other elements are added one by one.
+            let mut props = Vec::new();
+            let mut classes = Vec::new();
+            if is_custom.is_some() {
+                let (id, fields): (Vec<_>, Vec<_>) =
+                    attributes.into_iter().partition(|(k, _)| *k == "id");
+                attributes = id;
+                props = fields
+                    .into_iter()
+                    .map(|(k, v)| {
+                        let field = attribute_ident(k);
+                        let value = prop_value(v.as_ref());
+                        quote! { #field: #value }
+                    })
+                    .collect();
+            } else {
+                let (class, rest): (Vec<_>, Vec<_>) =
+                    attributes.into_iter().partition(|(k, _)| *k == "class");
+                attributes = rest;
+                classes = class.into_iter().filter_map(|(_, v)| v.as_ref()).collect();
+            }
+            let name = &element.name;
+            // find the path to the element type in the static list of the current namespace
+            let field = ns.elem_path(name);
 
             // no support for default element types yet.
             if field.is_none() && is_custom.is_none() {
                 let error = format!("element `{}` not implemented", name.to_lowercase());
-                return vec![(false, quote! { compile_error!(#error) })];
+                return ElementTokens::error(tag.error(&error));
             }
 
             // if the element is not custom set the path to it to the parent crate
             let elem_type = is_custom.clone().unwrap_or_else(|| {
-                let field = syn::parse2::<syn::Path>(quote! { webelements::elem::#field })
-                    .expect("custom element field name");
                 syn::parse2::<syn::Path>(quote! { webelements::Element<#field> })
                     .expect("custom element field path")
             });
 
-            // if the element is to be repeated set the field type to `Vec<Field_Type>`
+            // if the element is to be repeated set the field type to `Vec<Field_Type>`, if it is
+            // conditional to `Conditional<Field_Type>`
             let field_type = if is_repeat.is_some() {
                 syn::parse2::<syn::Path>(quote! { Vec<#elem_type> }).expect("field type name")
+            } else if condition.is_some() {
+                syn::parse2::<syn::Path>(quote! { webelements::Conditional<#elem_type> })
+                    .expect("field type name")
             } else {
                 elem_type.clone()
             };
 
             if let Some(field) = is_field.as_ref() {
                 let field = format_ident!("{}", field);
+                if field == "root" || refs.iter().any(|(f, _)| *f == field) {
+                    let error = format!("duplicate field `{}`", field);
+                    return ElementTokens::error(tag.attr_error("we_field", &error));
+                }
                 refs.push((field, field_type.clone()));
             }
 
             // recursivly generate code for all the children of this element;
-            let children = walk_dom(&element.children, refs);
+            let (ref_count, event_count) = (refs.len(), events.len());
+            let children = walk_dom(&element.children, refs, events, ns.children(name));
+            // the children of a list item or conditional are created later, outside of `build`
+            let is_dynamic = is_for.is_some() || condition.is_some();
+            if is_dynamic && (refs.len() != ref_count || events.len() != event_count) {
+                return ElementTokens::error(tag.error(
+                    "`we_field` and `we_on` can't be used inside `we_for`, `we_if` or `we_else`",
+                ));
+            }
 
-            let ident = format_ident!("_e_{}", element.name);
-            let text = element.children.iter().find_map(|n| {
-                if let Node::Text(s) = n {
-                    Some(s.clone())
-                } else {
-                    None
-                }
-            });
+            let ident = format_ident!("_e_{}", element.name.replace('-', "_"));
             // some variables will be iterators over Options types because they are optional and when iterated will not generate any code
-            let text = text.iter();
-
-            let classes = element.classes.iter();
+            let classes = classes.iter().map(|c| interpolate(&[c.segment()]));
             let attributes = attributes.iter().map(|&(k, v)| {
-                let v = v.clone().unwrap_or_else(|| "".to_owned());
+                let v = match v {
+                    Some(v) => interpolate(&[v.segment()]),
+                    None => quote! { "" },
+                };
                 quote! { (#k, #v) }
             });
             let mut field_ident = is_field.iter().map(|s| format_ident!("_m_{}", s));
             let repeat_field = field_ident.clone();
             let element_builder = match is_custom.as_ref() {
-                Some(custom) => {
+                Some(custom) if props.is_empty() => {
                     quote! { <#custom as webelements::WebElementBuilder>::build() }
                 }
+                Some(custom) => {
+                    // a struct expression can't use the associated type directly
+                    quote! {
+                        <#custom as webelements::WebElementBuilder>::build_with({
+                            type Props = <#custom as webelements::WebElementBuilder>::Props;
+                            Props { #( #props ),* }
+                        })
+                    }
+                }
                 None => quote! { <#elem_type>::new() },
             };
-            if is_field.is_some() && is_repeat.is_some() {
+            // a custom element is hydrated with the same props it is built with
+            let element_hydrator = match is_custom.as_ref() {
+                Some(custom) if props.is_empty() => quote! {
+                    <#custom as webelements::WebElementBuilder>::hydrate_from(_cursor, Default::default())
+                },
+                Some(custom) => quote! {
+                    <#custom as webelements::WebElementBuilder>::hydrate_from(_cursor, {
+                        type Props = <#custom as webelements::WebElementBuilder>::Props;
+                        Props { #( #props ),* }
+                    })
+                },
+                None => quote! {},
+            };
+            if is_field.is_some() && (is_repeat.is_some() || condition.is_some()) {
                 field_ident.next();
             }
-            let single = children
-                .iter()
-                .filter_map(|(r, c)| if !*r { Some(c) } else { None });
-            let lists = children
-                .iter()
-                .filter_map(|(r, c)| if *r { Some(c) } else { None });
-
-            let mut tokens = quote! {
+            let hydrate_field = field_ident.clone();
+            // the children are added in the order of the template
+            let append_children = children.iter().map(|c| {
+                let build = &c.build;
+                match c.kind {
+                    ElementKind::Single => quote! { #ident.append(&{#build})?; },
+                    ElementKind::Repeat(_) => quote! { #ident.append_list({#build})?; },
+                    ElementKind::List | ElementKind::Conditional | ElementKind::Text => {
+                        quote! { { let _parent = &#ident; #build } }
+                    }
+                }
+            });
+
+            let build = quote! {
+                #( #checks )*
                 let mut #ident = #element_builder?;
-                #( #ident.append(&{#single})?; )*
-                #( #ident.append_list({#lists})?; )*
+                #( #append_children )*
                 #( #ident.add_class(#classes); )*
                 #(
                     let (key, value) = #attributes;
                     #ident.set_attr(key, value)?;
                 )*
-                #( #ident.set_text(#text); )*
+                #( #ident.#signals; )*
                 #( #field_ident = Some(#ident.clone()); )*
+                #( #bindings.push(#ident.clone()); )*
                 #ident
             };
-            if let Some(n) = is_repeat {
-                let n = n as usize;
-                let iter = (0..n).map(|n| n.to_string());
-                tokens = quote! {
-                    let mut _elem_list = Vec::with_capacity(#n);
-                    #(_elem_list.push({
-                        let i = #iter;
-                        #tokens
-                    });)*
-                    #( #repeat_field = Some(_elem_list.clone()); )*
-                    _elem_list
-                };
-            }
-            elements.push((is_repeat.is_some(), tokens));
+
+            // when hydrating the children are already in the dom, they only need to be
+            // matched up with the template in order.
+            let child_hydrate = children.iter().map(|c| {
+                let hydrate = &c.hydrate;
+                quote! { let _parent = &#ident; #hydrate }
+            });
+            let field_ident = hydrate_field;
+            let hydrate = match (is_custom.as_ref(), field.as_ref()) {
+                (Some(_), _) => quote! {
+                    let mut #ident = #element_hydrator?;
+                    #( #ident.#signals; )*
+                    #( #field_ident = Some(#ident.clone()); )*
+                    #( #bindings.push(#ident.clone()); )*
+                    #ident
+                },
+                (None, field) => quote! {
+                    let mut #ident = _cursor.element::<#field>()?;
+                    {
+                        let mut _children = _cursor.children(&#ident);
+                        let _cursor = &mut _children;
+                        #( { #child_hydrate }; )*
+                        _children.finish()?;
+                    }
+                    #( #ident.#signals; )*
+                    #( #field_ident = Some(#ident.clone()); )*
+                    #( #bindings.push(#ident.clone()); )*
+                    #ident
+                },
+            };
+
+            let hydrate_conditional = repeat_field.clone();
+            let (kind, build, hydrate) = match (is_repeat, is_for, condition) {
+                (_, _, Some(condition)) => (
+                    ElementKind::Conditional,
+                    quote! {
+                        let _conditional = webelements::Conditional::from_source(
+                            _parent,
+                            &(#condition),
+                            move || Ok({ #build }),
+                        )?;
+                        #( #repeat_field = Some(_conditional.clone()); )*
+                    },
+                    // the element is hydrated if the condition holds, it was rendered then
+                    quote! {
+                        let _conditional = webelements::Conditional::hydrate(
+                            _parent,
+                            _cursor,
+                            &(#condition),
+                            move || Ok({ #build }),
+                            |_cursor| Ok({ #hydrate }),
+                        )?;
+                        #( #hydrate_conditional = Some(_conditional.clone()); )*
+                    },
+                ),
+                (Some(n), _, None) => (
+                    ElementKind::Repeat(n as usize),
+                    repeat_tokens(n as usize, build, repeat_field.clone()),
+                    repeat_tokens(n as usize, hydrate, repeat_field),
+                ),
+                (None, Some((pat, items)), None) => {
+                    let key = for_key.expect("checked above");
+                    let (build, hydrate) = list_tokens(pat, items, key, build, hydrate);
+                    (ElementKind::List, build, hydrate)
+                }
+                (None, None, None) => (ElementKind::Single, build, hydrate),
+            };
+            elements.push(ElementTokens {
+                kind,
+                build,
+                hydrate,
+            });
         }
     }
     elements
 }
 
-fn gen_element(dom: Dom, s_fields: &syn::FieldsNamed) -> DomParsed {
+/// the text of the text node at `i` in `dom`. whitespace at the start and end of an element is
+/// dropped and whitespace next to an element is kept as a single space, empty text has no node.
+fn node_text(dom: &[Node], i: usize) -> Option<Vec<Segment>> {
+    let text = match &dom[i] {
+        Node::Text(text) => text,
+        Node::Element(_) => return None,
+    };
+    let is_element =
+        |i: Option<usize>| matches!(i.and_then(|i| dom.get(i)), Some(Node::Element(_)));
+    let mut value = Vec::new();
+    if text.space_before && is_element(i.checked_sub(1)) {
+        push_text(&mut value, " ");
+    }
+    for segment in text.segments.iter() {
+        match segment {
+            Segment::Text(t) => push_text(&mut value, t),
+            expr => value.push(expr.clone()),
+        }
+    }
+    if text.space_after && is_element(Some(i + 1)) {
+        push_text(&mut value, " ");
+    }
+    Some(value).filter(|v| !v.is_empty())
+}
+
+/// code for a `<we_text we_field="name">` text node that is kept in a field to change its text
+/// later. when hydrating the text is split from the static text around it in the dom.
+fn text_field(
+    element: &Element,
+    dom: &[Node],
+    i: usize,
+    refs: &mut Vec<(Ident, syn::Path)>,
+) -> Result<ElementTokens, TokenStream> {
+    let tag = &element.spans;
+    let mut field = None;
+    let mut signal = None;
+    for (key, value) in element.attributes.iter() {
+        match (key.as_str(), value) {
+            ("we_field", Some(Value::Text(value, _))) => field = Some(format_ident!("{}", value)),
+            ("we_field", _) => return Err(tag.attr_error(key, "`we_field` needs a name as value")),
+            ("we_bind:text", Some(Value::Expr(expr))) => signal = Some(expr),
+            ("we_bind:text", _) => {
+                return Err(tag.attr_error(key, "`we_bind` needs a signal `{expr}` as value"))
+            }
+            _ => {
+                return Err(
+                    tag.attr_error(key, "`we_text` only accepts `we_field` and `we_bind:text`")
+                )
+            }
+        }
+    }
+    // the field keeps the binding, it is removed when the struct is dropped
+    let bind = signal.map(|signal| quote! { _text.bind_text(&(#signal)); });
+    let field = field.ok_or_else(|| tag.error("`we_text` needs a `we_field`"))?;
+    if field == "root" || refs.iter().any(|(f, _)| *f == field) {
+        let error = format!("duplicate field `{}`", field);
+        return Err(tag.attr_error("we_field", &error));
+    }
+    let mut text = Vec::new();
+    for child in element.children.iter() {
+        match child {
+            Node::Text(t) => {
+                if !text.is_empty() {
+                    push_text(&mut text, " ");
+                }
+                for segment in t.segments.iter() {
+                    match segment {
+                        Segment::Text(t) => push_text(&mut text, t),
+                        expr => text.push(expr.clone()),
+                    }
+                }
+            }
+            Node::Element(e) => return Err(e.spans.error("`we_text` can only contain text")),
+        }
+    }
+    let text = interpolate(&text);
+
+    // the text nodes around this one up to the elements before and after it are merged in the
+    // html, the length of their text is where this text starts and ends
+    let static_len = |nodes: &mut dyn Iterator<Item = usize>| {
+        let mut len = 0;
+        for j in nodes {
+            match &dom[j] {
+                Node::Text(t) if !t.has_expr() => {
+                    len += node_text(dom, j)
+                        .iter()
+                        .flatten()
+                        .filter_map(Segment::text)
+                        .map(|t| t.encode_utf16().count())
+                        .sum::<usize>();
+                }
+                Node::Element(e) if e.name != "we_text" => break,
+                _ => {
+                    return Err(tag.error(
+                        "`we_text` can't be next to text with an expression or another \
+                         `we_text` without an element between them, it could not be hydrated",
+                    ))
+                }
+            }
+        }
+        Ok(len as u32)
+    };
+    let before = static_len(&mut (0..i).rev())?;
+    let after = static_len(&mut (i + 1..dom.len()))?;
+
+    refs.push((
+        field.clone(),
+        syn::parse2(quote! { webelements::TextNode }).expect("text node type"),
+    ));
+    let field = format_ident!("_m_{}", field);
+    Ok(ElementTokens {
+        kind: ElementKind::Text,
+        build: quote! {
+            let _text = _parent.append_text(#text)?;
+            #bind
+            #field = Some(_text);
+        },
+        hydrate: quote! {
+            let _text = _cursor.text(#before, #after)?;
+            #bind
+            #field = Some(_text);
+        },
+    })
+}
+
+/// wraps the code for a single element in a list that builds it for each item, returns the code
+/// that builds the list and the code that hydrates it
+fn list_tokens(
+    pat: TokenStream,
+    items: TokenStream,
+    key: TokenStream,
+    build: TokenStream,
+    hydrate: TokenStream,
+) -> (TokenStream, TokenStream) {
+    (
+        quote! {
+            webelements::ElementList::from_source(
+                _parent,
+                &(#items),
+                |#pat| #key,
+                move |#pat| Ok({ #build }),
+            )?;
+        },
+        quote! {
+            webelements::ElementList::hydrate(
+                _parent,
+                _cursor,
+                &(#items),
+                |#pat| #key,
+                move |#pat| Ok({ #build }),
+                |_cursor, #pat| Ok({ #hydrate }),
+            )?;
+        },
+    )
+}
+
+/// wraps the code for a single element so it is evaluated `n` times and collected in a `Vec`
+fn repeat_tokens(
+    n: usize,
+    tokens: TokenStream,
+    repeat_field: impl Iterator<Item = Ident>,
+) -> TokenStream {
+    let iter = 0..n;
+    quote! {
+        let mut _elem_list = Vec::with_capacity(#n);
+        #(_elem_list.push({
+            let i: usize = #iter;
+            #tokens
+        });)*
+        #( #repeat_field = Some(_elem_list.clone()); )*
+        _elem_list
+    }
+}
+
+fn gen_element(nodes: &[Node], s_fields: &syn::FieldsNamed) -> DomParsed {
     let mut refs: Vec<(Ident, syn::Path)> = Vec::new();
+    let mut events: Vec<EventBinding> = Vec::new();
     let mut errors = quote! {};
-    if dom.children.len() != 1 {
-        errors = quote! {
-            #errors
-            compile_error!("DOM should contain 1 root")
+    // report an error on every node after the root
+    for node in nodes.iter().skip(1) {
+        let span = match node {
+            Node::Element(e) => e.spans.tag,
+            Node::Text(t) => t.span,
         };
+        let error = span.error("DOM should contain 1 root");
+        errors = quote! { #errors #error };
     }
+    let root: Option<&Element> = match nodes.first() {
+        Some(Node::Element(e)) => Some(e),
+        Some(Node::Text(t)) => {
+            let error = t.span.error("the root must be an element");
+            errors = quote! { #errors #error };
+            None
+        }
+        None => {
+            errors = quote! { #errors compile_error!("no root found"); };
+            None
+        }
+    };
+    let root_tag = match root {
+        Some(root) => root,
+        None => {
+            return DomParsed {
+                fields: Default::default(),
+                root_type: None,
+                root_is_element: true,
+                props: quote! { () },
+                build: quote! {},
+                errors,
+            }
+        }
+    };
     let mut root_is_element = true;
-    let root_type = dom
-        .children
-        .first()
-        .map(|e| {
-            if let Node::Element(e) = e {
-                let name = ELEM_INPUT.iter().find_map(|s| {
-                    if e.name.to_lowercase() == s.0 {
-                        Some(format_ident!("{}", s.1))
-                    } else {
-                        None
-                    }
-                });
-                if let Some(name) = name {
-                    syn::parse2::<syn::Path>(quote! { webelements::elem::#name }).ok()
-                } else {
-                    root_is_element = false;
-                    let name = format_ident!("{}", e.name);
-                    syn::parse2::<syn::Path>(quote! { #name }).ok()
-                }
-            } else {
-                None
+    let root_type = match Namespace::Html
+        .element(&root_tag.name)
+        .elem_path(&root_tag.name)
+    {
+        Some(path) => Some(path),
+        None => {
+            root_is_element = false;
+            syn::parse2::<syn::Path>(root_tag.path.clone()).ok()
+        }
+    };
+    // the props type is set with `we_props` on the root, without it there are no props
+    let props = match root_tag.attribute("we_props") {
+        Some(Some(props)) => match props.parse::<syn::Type>() {
+            Ok(ty) => quote! { #ty },
+            Err(_) => {
+                let error = root_tag
+                    .spans
+                    .attr_error("we_props", "`we_props` must be a type");
+                errors = quote! { #errors #error };
+                quote! { () }
             }
-        })
-        .unwrap_or_else(|| {
-            errors = quote! { #errors; compile_error!("no root found") };
-            None
-        });
-    let elements = walk_dom(&dom.children, &mut refs);
-    let root = &elements.first().expect("element needs to have a root").1;
+        },
+        Some(_) => {
+            let error = root_tag
+                .spans
+                .attr_error("we_props", "`we_props` needs a type as value");
+            errors = quote! { #errors #error };
+            quote! { () }
+        }
+        None => quote! { () },
+    };
+    let elements = walk_dom(&nodes[..1], &mut refs, &mut events, Namespace::Html);
+    let root = elements.first().expect("element needs to have a root");
+    if root.kind != ElementKind::Single {
+        let error = root_tag
+            .spans
+            .error("the root can't use `we_repeat`, `we_for` or `we_if`");
+        errors = quote! { #errors #error };
+    }
+    let root_build = &root.build;
+    let root_hydrate = &root.hydrate;
     let ref_name: Vec<Ident> = refs.iter().map(|(s, _)| format_ident!("{}", s)).collect();
     let ref_value: Vec<Ident> = refs
         .iter()
         .map(|(s, _)| format_ident!("_m_{}", s))
         .collect();
-    let fields = s_fields.named.iter().map(|f| f.ident.as_ref()).flatten();
-    let types = s_fields.named.iter().map(|f| &f.ty);
+    let fields: Vec<&Ident> = s_fields
+        .named
+        .iter()
+        .filter_map(|f| f.ident.as_ref())
+        .collect();
+    let types: Vec<&syn::Type> = s_fields.named.iter().map(|f| &f.ty).collect();
+    let event_targets: Vec<&Ident> = events.iter().map(|e| &e.targets).collect();
+    // the handlers are called with a clone of the struct, bound after `init` so the clone
+    // has the initialized state. the clone does not keep the root alive, the root keeps the
+    // listeners so they are removed when the struct is dropped.
+    let bind_events = events.iter().map(|e| {
+        let EventBinding {
+            targets,
+            event,
+            handler,
+        } = e;
+        quote! {
+            for _target in #targets.iter() {
+                let _this = webelements::WebElementBuilder::unbound(&element);
+                let _listener = _target.on_event(#event, move |e| (#handler)(&_this, e))?;
+                element.root.keep_listener(_listener);
+            }
+        }
+    });
+    let bind_events = quote! { #( #bind_events )* };
     let token = quote!(
-        fn build() -> webelements::Result<Self> {
+        // expressions in the template can use `props`
+        #[allow(unused_variables)]
+        fn build_with(props: Self::Props) -> webelements::Result<Self> {
+            #( let mut #ref_value = None; )*
+            #( let mut #event_targets = Vec::new(); )*
+            let _e_root = {#root_build};
+            let mut element = Self {
+                root: _e_root,
+                #( #fields: <#types as Default>::default(),)*
+                #( #ref_name: #ref_value.unwrap(),)*
+            };
+            <Self as webelements::WebElement>::init(&mut element)?;
+            #bind_events
+            webelements::lifecycle::register(&element)?;
+            Ok(element)
+        }
+
+        // the expressions that are evaluated when hydrating can use `props` as well
+        #[allow(unused_variables)]
+        fn hydrate_from(_cursor: &mut webelements::Hydrator, props: Self::Props) -> webelements::Result<Self> {
             #( let mut #ref_value = None; )*
-            let _e_root = {#root};
+            #( let mut #event_targets = Vec::new(); )*
+            let _e_root = {#root_hydrate};
             let mut element = Self {
                 root: _e_root,
                 #( #fields: <#types as Default>::default(),)*
                 #( #ref_name: #ref_value.unwrap(),)*
             };
             <Self as webelements::WebElement>::init(&mut element)?;
+            #bind_events
+            webelements::lifecycle::register(&element)?;
             Ok(element)
         }
     );
@@ -316,6 +1363,7 @@ fn gen_element(dom: Dom, s_fields: &syn::FieldsNamed) -> DomParsed {
             .collect(),
         root_type,
         root_is_element,
+        props,
         build: token,
         errors,
     }
@@ -335,9 +1383,14 @@ pub fn we_builder(
                     fields,
                     root_type,
                     root_is_element,
+                    props,
                     build,
                     errors,
                 } = parse_args(args.into(), s_fields);
+                // without a root there is nothing to generate, only report the errors
+                if root_type.is_none() {
+                    return quote! { #errors #ast }.into();
+                }
                 let elem = if root_is_element {
                     quote! { #root_type }
                 } else {
@@ -348,6 +1401,18 @@ pub fn we_builder(
                 } else {
                     quote! { #root_type }
                 };
+                let unbound_root = if root_is_element {
+                    quote! { self.root.unbound() }
+                } else {
+                    quote! { <#root_type as webelements::WebElementBuilder>::unbound(&self.root) }
+                };
+                // the fields are cloned one by one, the struct doesn't need to be `Clone`
+                let cloned: Vec<Ident> = s_fields
+                    .named
+                    .iter()
+                    .chain(fields.iter())
+                    .filter_map(|f| f.ident.clone())
+                    .collect();
                 s_fields.named.push(
                     syn::Field::parse_named
                         .parse2(quote! { pub root: #root })
@@ -363,8 +1428,16 @@ pub fn we_builder(
 
                     impl webelements::WebElementBuilder for #ident {
                         type Elem = #elem;
+                        type Props = #props;
 
                         #build
+
+                        fn unbound(&self) -> Self {
+                            Self {
+                                root: #unbound_root,
+                                #( #cloned: Clone::clone(&self.#cloned), )*
+                            }
+                        }
                     }
 
                     impl AsRef<webelements::Element<<Self as webelements::WebElementBuilder>::Elem>> for #ident {
@@ -392,42 +1465,495 @@ pub fn we_builder(
     tokens
 }
 
-#[proc_macro_derive(WebElement)]
+#[proc_macro_derive(WebElement, attributes(we))]
 pub fn we_element_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let ast = parse_macro_input!(input as DeriveInput);
-    let ident = ast.ident;
+    web_element::derive(ast)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+#[proc_macro_derive(WeForm, attributes(we_form))]
+pub fn we_form_derive(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    form::derive(ast)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
 
-    (quote! {
-        impl webelements::WebElement for #ident {
-            fn init(&mut self) -> webelements::Result<()> { Ok(()) }
+/// an identifier for an attribute name, `data-x` becomes `data_x` and keywords are raw
+fn attribute_ident(name: &str) -> Ident {
+    let name = name.replace('-', "_");
+    // syn accepts the keywords that were added in the 2018 edition as identifiers
+    match syn::parse_str::<Ident>(&name) {
+        Ok(ident) if !["async", "await", "dyn", "try"].contains(&name.as_str()) => ident,
+        _ => Ident::new_raw(&name, Span::call_site()),
+    }
+}
+
+/// typed getters and setters for the attributes of the element type `name` with tag `tag`
+fn gen_accessors(tag: &str, name: &Ident) -> TokenStream {
+    let methods = attributes::accessors(tag).map(|(attr, accessor)| {
+        let getter = attribute_ident(attr);
+        let setter = format_ident!("set_{}", attr.replace('-', "_"));
+        let get_doc = format!("the `{}` attribute", attr);
+        let set_doc = format!("sets the `{}` attribute", attr);
+        match accessor {
+            attributes::Accessor::Text => quote! {
+                #[doc = #get_doc]
+                pub fn #getter(&self) -> Option<String> {
+                    self.attr(#attr)
+                }
+
+                #[doc = #set_doc]
+                pub fn #setter(&self, value: impl AsRef<str>) -> crate::Result<()> {
+                    self.set_attr(#attr, value)
+                }
+            },
+            attributes::Accessor::Display => quote! {
+                #[doc = #get_doc]
+                pub fn #getter(&self) -> Option<String> {
+                    self.attr(#attr)
+                }
+
+                #[doc = #set_doc]
+                pub fn #setter<T: ToString>(&self, value: T) -> crate::Result<()> {
+                    self.set_attr(#attr, value.to_string())
+                }
+            },
+            attributes::Accessor::Bool => quote! {
+                #[doc = #get_doc]
+                pub fn #getter(&self) -> bool {
+                    self.attr(#attr).is_some()
+                }
+
+                #[doc = #set_doc]
+                pub fn #setter(&self, value: bool) -> crate::Result<()> {
+                    self.set_bool_attr(#attr, value)
+                }
+            },
+            attributes::Accessor::Number(ty) => {
+                let ty = format_ident!("{}", ty);
+                quote! {
+                    #[doc = #get_doc]
+                    pub fn #getter(&self) -> Option<#ty> {
+                        self.attr(#attr).and_then(|v| v.trim().parse().ok())
+                    }
+
+                    #[doc = #set_doc]
+                    pub fn #setter(&self, value: #ty) -> crate::Result<()> {
+                        self.set_attr(#attr, value.to_string())
+                    }
+                }
+            }
+            attributes::Accessor::Property(ty) => {
+                let get_doc = format!("the `{}` property", attr);
+                let set_doc = format!("sets the `{}` property", attr);
+                // setting a property can't fail in the browser, so the setter has no result. on
+                // the server setting the attribute can only fail on an invalid name.
+                let (ty, set_ty, set_value, ssr_get, ssr_set) = if *ty == "bool" {
+                    (
+                        quote! { bool },
+                        quote! { bool },
+                        quote! { value },
+                        quote! { self.attr(#attr).is_some() },
+                        quote! { let _ = self.set_bool_attr(#attr, value); },
+                    )
+                } else {
+                    (
+                        quote! { String },
+                        quote! { impl ToString },
+                        quote! { &value.to_string() },
+                        quote! { self.attr(#attr).unwrap_or_default() },
+                        quote! { let _ = self.set_attr(#attr, value.to_string()); },
+                    )
+                };
+                quote! {
+                    #[doc = #get_doc]
+                    #[cfg(not(feature = "ssr"))]
+                    pub fn #getter(&self) -> #ty {
+                        self.element.#getter()
+                    }
+
+                    #[doc = #set_doc]
+                    #[cfg(not(feature = "ssr"))]
+                    pub fn #setter(&self, value: #set_ty) {
+                        self.element.#setter(#set_value)
+                    }
+
+                    #[doc = #get_doc]
+                    #[cfg(feature = "ssr")]
+                    pub fn #getter(&self) -> #ty {
+                        #ssr_get
+                    }
+
+                    #[doc = #set_doc]
+                    #[cfg(feature = "ssr")]
+                    pub fn #setter(&self, value: #set_ty) {
+                        #ssr_set
+                    }
+                }
+            }
         }
-    })
-    .into()
+    });
+    let methods: Vec<TokenStream> = methods.collect();
+    if methods.is_empty() {
+        return quote! {};
+    }
+    quote! {
+        impl crate::Element<#name> {
+            #( #methods )*
+        }
+    }
 }
 
-#[proc_macro]
-pub fn element_types(_input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let elems = ELEM_INPUT.iter().map(|s| s.0);
-    let names = ELEM_INPUT.iter().map(|s| format_ident!("{}", s.1));
-    let types = ELEM_INPUT.iter().map(|s| format_ident!("{}", s.2));
-    let tokens = quote! {
+fn gen_element_types(ns: Namespace) -> TokenStream {
+    let table = ns.table();
+    let elems = table.iter().map(|s| s.0);
+    let names = table.iter().map(|s| format_ident!("{}", s.1));
+    let types = table.iter().map(|s| format_ident!("{}", s.2));
+    let uri = match ns.uri() {
+        Some(uri) => quote! { Some(#uri) },
+        None => quote! { None },
+    };
+    // only html elements have typed accessors
+    let accessors = table.iter().filter(|_| ns == Namespace::Html).map(|s| {
+        let name = format_ident!("{}", s.1);
+        gen_accessors(s.0, &name)
+    });
+    quote! {
+        #( #accessors )*
+
         #(
         #[derive(Debug, Clone)]
         pub struct #names;
         impl ElemTy for #names {
             type Elem = web_sys::#types;
+            const TAG: &'static str = #elems;
+            const NAMESPACE: Option<&'static str> = #uri;
 
             fn make() -> crate::Result<Self::Elem> {
-                crate::document()?
-                    .create_element(#elems)?
-                    .dyn_into::<web_sys::#types>()
-                    .map_err(|e| crate::Error::Cast(std::any::type_name::<web_sys::#types>()))
+                // the browser document, `crate::document` is the in-memory one with `ssr`
+                let document = crate::window()?.document().ok_or(crate::Error::Document)?;
+                match Self::NAMESPACE {
+                    Some(ns) => document.create_element_ns(Some(ns), Self::TAG)?,
+                    None => document.create_element(Self::TAG)?,
+                }
+                .dyn_into::<web_sys::#types>()
+                .map_err(|_| crate::Error::Cast(std::any::type_name::<web_sys::#types>()))
             }
         }
         )*
+    }
+}
+
+#[proc_macro]
+pub fn element_types(_input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let html = gen_element_types(Namespace::Html);
+    let svg = gen_element_types(Namespace::Svg);
+    let math = gen_element_types(Namespace::MathMl);
+    let tokens = quote! {
+        #html
+
+        pub mod svg {
+            use super::ElemTy;
+            use wasm_bindgen::JsCast;
+            #svg
+        }
+
+        pub mod math {
+            use super::ElemTy;
+            use wasm_bindgen::JsCast;
+            #math
+        }
     };
     tokens.into()
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_interpolate() {
+        let text = |t: &str| Segment::Text(t.to_owned());
+        assert_eq!(
+            interpolate(&[text("plain {text} __we_expr_0__")]).to_string(),
+            quote! { "plain {text} __we_expr_0__" }.to_string()
+        );
+        let segments = [
+            text("hello "),
+            Segment::Expr(quote! { props.name }),
+            text(" {"),
+            Segment::Expr(quote! { i }),
+            text("} __we_expr_0__"),
+        ];
+        assert_eq!(
+            interpolate(&segments).to_string(),
+            quote! { format!("hello {} {{{}}} __we_expr_0__", (props.name), (i)) }.to_string()
+        );
+    }
+
+    /// the segments as text with the expressions in braces
+    fn show(segments: &[Segment]) -> String {
+        segments
+            .iter()
+            .map(|s| match s {
+                Segment::Text(t) => t.clone(),
+                Segment::Expr(e) => format!("{{{}}}", e),
+            })
+            .collect()
+    }
+
+    fn show_attributes(element: &Element) -> Vec<(String, Option<String>)> {
+        element
+            .attributes
+            .iter()
+            .map(|(k, v)| (k.clone(), v.as_ref().map(|v| show(&[v.segment()]))))
+            .collect()
+    }
+
+    fn parse(template: &str) -> Template {
+        Template::parse(TokenStream::from_str(template).unwrap()).unwrap()
+    }
+
+    fn parse_error(template: &str) -> String {
+        Template::parse(TokenStream::from_str(template).unwrap())
+            .unwrap_err()
+            .to_string()
+    }
+
+    #[test]
+    fn test_template() {
+        let template = parse(
+            r#"<div class="a b" id=main we_repeat=2 data-1x="-" hidden>
+                <p data-index={i} we_on:click=Self::f we_element />
+                <input type="text">
+                <!-- a comment -->
+                <span>don't {props.name}", quoted <text> #1"</span>
+                <crate::Label we_element></crate::Label>
+            </div>"#,
+        );
+        let root = match &template.nodes[..] {
+            [Node::Element(root)] => root,
+            nodes => panic!("unexpected nodes {:?}", nodes),
+        };
+        assert_eq!(
+            show_attributes(root),
+            vec![
+                ("class".to_owned(), Some("a b".to_owned())),
+                ("id".to_owned(), Some("main".to_owned())),
+                ("we_repeat".to_owned(), Some("2".to_owned())),
+                ("data-1x".to_owned(), Some("-".to_owned())),
+                ("hidden".to_owned(), None),
+            ]
+        );
+        let children: Vec<&Element> = root
+            .children
+            .iter()
+            .map(|n| match n {
+                Node::Element(e) => e,
+                n => panic!("unexpected node {:?}", n),
+            })
+            .collect();
+        let names: Vec<&str> = children.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["p", "input", "span", "crate::Label"]);
+        assert_eq!(
+            show_attributes(children[0])[..2],
+            [
+                ("data-index".to_owned(), Some("{i}".to_owned())),
+                ("we_on:click".to_owned(), Some("Self::f".to_owned())),
+            ]
+        );
+        match &children[2].children[..] {
+            [Node::Text(text)] => assert_eq!(
+                show(&text.segments),
+                "don't {props . name}, quoted <text> #1"
+            ),
+            nodes => panic!("unexpected nodes {:?}", nodes),
+        }
+    }
+
+    #[test]
+    fn test_template_text() {
+        let template = parse(
+            "<p>\n  Hello <b>world</b>! <i>a</i>\"b \"<we_text we_field=\"t\">c</we_text> </p>",
+        );
+        let root = match &template.nodes[..] {
+            [Node::Element(root)] => root,
+            nodes => panic!("unexpected nodes {:?}", nodes),
+        };
+        let texts: Vec<Option<String>> = (0..root.children.len())
+            .map(|i| node_text(&root.children, i).map(|t| show(&t)))
+            .collect();
+        assert_eq!(
+            texts,
+            vec![
+                Some("Hello ".to_owned()),
+                None,
+                Some("! ".to_owned()),
+                None,
+                Some("b ".to_owned()),
+                None,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_attribute_values() {
+        let template = parse(
+            r#"<ul we_props="crate::Props<'_>">
+                <li we_for="todo in props.todos" we_on:click=Self::f data-x=-1 />
+                <li we_for={(i, x) in items.iter().enumerate()} />
+            </ul>"#,
+        );
+        let root = match &template.nodes[0] {
+            Node::Element(root) => root,
+            node => panic!("unexpected node {:?}", node),
+        };
+        let value = |element: &Element, key: &str| element.attribute(key).unwrap().clone().unwrap();
+        let props = value(root, "we_props").parse::<syn::Type>().unwrap();
+        assert_eq!(quote! { #props }.to_string(), "crate :: Props < '_ >");
+        let items: Vec<&Element> = root
+            .children
+            .iter()
+            .map(|n| match n {
+                Node::Element(e) => e,
+                n => panic!("unexpected node {:?}", n),
+            })
+            .collect();
+        let (pat, list) = value(items[0], "we_for").parse_with(for_in).unwrap();
+        assert_eq!(
+            (pat.to_string(), list.to_string()),
+            ("todo".into(), "props . todos".into())
+        );
+        let (pat, list) = value(items[1], "we_for").parse_with(for_in).unwrap();
+        assert_eq!(
+            (pat.to_string(), list.to_string()),
+            ("(i , x)".into(), "items . iter () . enumerate ()".into())
+        );
+        let handler = handler_expr(&value(items[0], "we_on:click")).unwrap();
+        assert_eq!(handler.to_string(), "Self :: f");
+        assert_eq!(
+            prop_value(Some(&value(items[0], "data-x"))).to_string(),
+            "- 1"
+        );
+    }
+
+    #[test]
+    fn test_template_spans() {
+        let template = parse(
+            r#"<div class="a" we_repeat=2>
+                <p data-index={i} we_on:click="Self::f" we_element />
+                </div>"#,
+        );
+        let root = match &template.nodes[0] {
+            Node::Element(root) => root,
+            node => panic!("unexpected node {:?}", node),
+        };
+        let keys: Vec<&str> = root
+            .spans
+            .attributes
+            .iter()
+            .map(|(k, _)| k.as_str())
+            .collect();
+        assert_eq!(keys, vec!["class", "we_repeat"]);
+        let child = match &root.children[0] {
+            Node::Element(child) => child,
+            node => panic!("unexpected node {:?}", node),
+        };
+        let keys: Vec<&str> = child
+            .spans
+            .attributes
+            .iter()
+            .map(|(k, _)| k.as_str())
+            .collect();
+        assert_eq!(keys, vec!["data-index", "we_on:click", "we_element"]);
+    }
+
+    #[test]
+    fn test_attribute_check() {
+        assert!(attributes::check("div", "class", Some("a")).is_ok());
+        assert!(attributes::check("div", "data-anything", None).is_ok());
+        assert!(attributes::check("div", "aria-label", Some("x")).is_ok());
+        assert!(attributes::check("input", "type", None).is_ok());
+        assert!(attributes::check("Input", "TYPE", Some("Text")).is_ok());
+        assert_eq!(
+            attributes::check("div", "clas", Some("a")),
+            Err("unknown attribute `clas` on `<div>`, did you mean `class`?".to_owned())
+        );
+        assert_eq!(
+            attributes::check("input", "tpye", Some("text")),
+            Err("unknown attribute `tpye` on `<input>`, did you mean `type`?".to_owned())
+        );
+        assert_eq!(
+            attributes::check("div", "href", Some("/")),
+            Err("unknown attribute `href` on `<div>`".to_owned())
+        );
+        assert!(attributes::check("input", "type", Some("txt"))
+            .unwrap_err()
+            .starts_with(
+                r#"invalid value "txt" for `type` on `<input>`, expected one of "button""#
+            ));
+        assert!(attributes::check("div", "dir", Some("")).is_err());
+    }
+
+    #[test]
+    fn test_template_errors() {
+        assert_eq!(
+            parse_error("<div><p></div>"),
+            "expected `</p>`, found `</div>`"
+        );
+        assert_eq!(parse_error("<div>"), "`<div>` is never closed");
+        assert_eq!(
+            parse_error("<div></div></p>"),
+            "closing tag without an opening tag"
+        );
+        assert_eq!(parse_error("<div a=></div>"), "expected an attribute value");
+    }
+
+    #[test]
+    fn test_web_element_hooks() {
+        let derive = |input: &str| web_element::derive(syn::parse_str(input).unwrap());
+        let tokens = derive(r#"#[we(init = "Self::setup", drop = "Self::stop")] struct A {}"#)
+            .unwrap()
+            .to_string();
+        assert!(tokens.contains("fn dropped"));
+        assert!(!tokens.contains("fn mounted"));
+        let error = |input: &str| derive(input).unwrap_err().to_string();
+        assert_eq!(
+            error(r#"#[we(mount = "Self::mount")] struct A {}"#),
+            "expected `init`, `mounted`, `before_unmount`, `unmounted`, `updated` or `drop`"
+        );
+        assert_eq!(
+            error(r#"#[we(init = "Self::a", init = "Self::b")] struct A {}"#),
+            "`init` is set more than once"
+        );
+        assert_eq!(error(r#"#[we(init = 1)] struct A {}"#), "expected a string");
+        // `skip` is only a flag of `WeForm`
+        assert_eq!(
+            error(r#"#[we(skip)] struct A {}"#),
+            "expected `init`, `mounted`, `before_unmount`, `unmounted`, `updated` or `drop`"
+        );
+    }
+
+    #[test]
+    fn test_form_options() {
+        let derive = |input: &str| form::derive(syn::parse_str(input).unwrap());
+        let error = |input: &str| derive(input).unwrap_err().to_string();
+        assert_eq!(
+            error(r#"#[we_form(view = "V", skip)] struct A {}"#),
+            "expected `view` or `validate`"
+        );
+        assert_eq!(
+            error(r#"#[we_form(view = "V")] struct A { #[we_form(skip = "a")] a: bool }"#),
+            "expected `field`, `validate` or `skip`"
+        );
+        let tokens = derive(r#"#[we_form(view = "V")] struct A { #[we_form(skip)] a: bool }"#)
+            .unwrap()
+            .to_string();
+        assert!(!tokens.contains("\"a\""));
+    }
+}
@@ -0,0 +1,63 @@
+//! the `#[name(key = "value", flag)]` attributes of the derives, each derive passes the keys
+//! and flags it accepts so the others are an error at the attribute.
+
+use proc_macro2::Span;
+use syn::{spanned::Spanned, Ident, Lit, Meta, NestedMeta};
+
+/// the `key = "value"` pairs and flags of the `#[name(..)]` attributes, a flag has an empty value
+pub(crate) fn parse(
+    attrs: &[syn::Attribute],
+    name: &str,
+    keys: &[&str],
+    flags: &[&str],
+) -> syn::Result<Vec<(Ident, syn::LitStr)>> {
+    let mut options = Vec::new();
+    for attr in attrs.iter().filter(|a| a.path.is_ident(name)) {
+        let list = match attr.parse_meta()? {
+            Meta::List(list) => list,
+            meta => {
+                return Err(syn::Error::new(
+                    meta.span(),
+                    format!("expected `{}(key = \"value\")`", name),
+                ))
+            }
+        };
+        for nested in list.nested {
+            match nested {
+                NestedMeta::Meta(Meta::NameValue(nv)) => {
+                    let key = match nv.path.get_ident() {
+                        Some(key) if keys.iter().any(|k| key == k) => key.clone(),
+                        _ => return Err(unexpected(nv.path.span(), keys, flags)),
+                    };
+                    match nv.lit {
+                        Lit::Str(value) => options.push((key, value)),
+                        lit => return Err(syn::Error::new(lit.span(), "expected a string")),
+                    }
+                }
+                NestedMeta::Meta(Meta::Path(path)) => match path.get_ident() {
+                    Some(flag) if flags.iter().any(|f| flag == f) => {
+                        options.push((flag.clone(), syn::LitStr::new("", path.span())))
+                    }
+                    _ => return Err(unexpected(path.span(), keys, flags)),
+                },
+                nested => return Err(unexpected(nested.span(), keys, flags)),
+            }
+        }
+    }
+    Ok(options)
+}
+
+/// "expected `a`, `b` or `c`" with the accepted keys and flags
+fn unexpected(span: Span, keys: &[&str], flags: &[&str]) -> syn::Error {
+    let names: Vec<String> = keys
+        .iter()
+        .chain(flags)
+        .map(|name| format!("`{}`", name))
+        .collect();
+    let message = match names.split_last() {
+        Some((last, [])) => format!("expected {}", last),
+        Some((last, rest)) => format!("expected {} or {}", rest.join(", "), last),
+        None => "expected no options".to_owned(),
+    };
+    syn::Error::new(span, message)
+}
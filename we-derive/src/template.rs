@@ -0,0 +1,409 @@
+//! Parser for the html-like templates of `we_builder`. The template is parsed from the rust
+//! tokens directly so names like `data-index` and `we_on:click` can be written as they are in
+//! html, attribute values can be rust expressions and errors point at the tokens they are about.
+
+use proc_macro2::{Delimiter, Ident, Spacing, TokenStream, TokenTree};
+use syn::{
+    ext::IdentExt,
+    parse::{Parse, ParseStream, Parser},
+    LitStr, Token,
+};
+
+use crate::{SpanRange, TagSpans};
+
+/// html elements that never have children, they don't need to be closed
+static VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+#[derive(Debug)]
+pub(crate) enum Node {
+    Element(Element),
+    Text(Text),
+}
+
+/// a part of a text or an attribute value
+#[derive(Debug, Clone)]
+pub(crate) enum Segment {
+    /// text that is used as it is
+    Text(String),
+    /// the tokens of a `{expr}`, its `Display` value is used
+    Expr(TokenStream),
+}
+
+impl Segment {
+    /// the text if the segment is not an `{expr}`
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            Segment::Text(text) => Some(text),
+            Segment::Expr(_) => None,
+        }
+    }
+}
+
+/// the value of an attribute
+#[derive(Debug, Clone)]
+pub(crate) enum Value {
+    /// the text of a string literal, or a number, word or path written without quotes, and the
+    /// tokens of the value
+    Text(String, TokenStream),
+    /// the tokens of an `{expr}`
+    Expr(TokenStream),
+}
+
+impl Value {
+    /// the text if the value is not an `{expr}`
+    pub fn text(&self) -> Option<&str> {
+        match self {
+            Value::Text(text, _) => Some(text),
+            Value::Expr(_) => None,
+        }
+    }
+
+    /// the value as part of a text
+    pub fn segment(&self) -> Segment {
+        match self {
+            Value::Text(text, _) => Segment::Text(text.clone()),
+            Value::Expr(expr) => Segment::Expr(expr.clone()),
+        }
+    }
+
+    /// parses the value as rust code. the contents of a string literal are parsed with the span
+    /// of the literal, other values are parsed from their tokens.
+    pub fn parse_with<F: Parser>(&self, parser: F) -> syn::Result<F::Output> {
+        let tokens = match self {
+            Value::Text(_, tokens) => tokens,
+            Value::Expr(expr) => return parser.parse2(expr.clone()),
+        };
+        match syn::parse2::<LitStr>(tokens.clone()) {
+            Ok(lit) => lit.parse_with(parser),
+            Err(_) => parser.parse2(tokens.clone()),
+        }
+    }
+
+    pub fn parse<T: Parse>(&self) -> syn::Result<T> {
+        self.parse_with(T::parse)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Element {
+    pub name: String,
+    /// the tokens of the name, the path of a custom element
+    pub path: TokenStream,
+    /// the attributes in the order of the template
+    pub attributes: Vec<(String, Option<Value>)>,
+    pub children: Vec<Node>,
+    pub spans: TagSpans,
+}
+
+impl Element {
+    /// the value of the attribute `key`, `Some(None)` if it has no value
+    pub fn attribute(&self, key: &str) -> Option<&Option<Value>> {
+        self.attributes
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct Text {
+    /// the text with its whitespace collapsed and the `{expr}` groups in it
+    pub segments: Vec<Segment>,
+    pub span: SpanRange,
+    /// whether the text is separated by a space from an element before it
+    pub space_before: bool,
+    /// whether the text is separated by a space from an element after it
+    pub space_after: bool,
+}
+
+impl Text {
+    /// whether the text has an `{expr}` in it
+    pub fn has_expr(&self) -> bool {
+        self.segments.iter().any(|s| matches!(s, Segment::Expr(_)))
+    }
+}
+
+/// the nodes of a template
+#[derive(Debug)]
+pub(crate) struct Template {
+    pub nodes: Vec<Node>,
+}
+
+impl Template {
+    pub fn parse(tokens: TokenStream) -> syn::Result<Template> {
+        let nodes = (|input: ParseStream| {
+            let nodes = nodes(input)?;
+            if !input.is_empty() {
+                // only a closing tag stops the nodes before the end
+                return Err(input.error("closing tag without an opening tag"));
+            }
+            Ok(nodes)
+        })
+        .parse2(tokens)?;
+        Ok(Template { nodes })
+    }
+}
+
+/// the nodes up to the end of the input or the next closing tag
+fn nodes(input: ParseStream) -> syn::Result<Vec<Node>> {
+    let mut nodes = Vec::new();
+    while !input.is_empty() {
+        if input.peek(Token![<]) && input.peek2(Token![/]) {
+            break;
+        } else if input.peek(Token![<]) && input.peek2(Token![!]) {
+            comment(input)?;
+        } else if input.peek(Token![<]) {
+            nodes.push(Node::Element(element(input)?));
+        } else {
+            nodes.push(Node::Text(text(input)?));
+        }
+    }
+    Ok(nodes)
+}
+
+fn element(input: ParseStream) -> syn::Result<Element> {
+    let open: Token![<] = input.parse()?;
+    let (name, name_span, path) = name(input, "element")?;
+    let tag = SpanRange::new(open.span, name_span.end);
+
+    let mut attributes = Vec::new();
+    let mut attribute_spans = Vec::new();
+    let closed = loop {
+        if input.peek(Token![/]) && input.peek2(Token![>]) {
+            input.parse::<Token![/]>()?;
+            input.parse::<Token![>]>()?;
+            break true;
+        } else if input.peek(Token![>]) {
+            input.parse::<Token![>]>()?;
+            break false;
+        } else if input.is_empty() {
+            return Err(tag.syn_error(&format!("`<{}` is missing a `>`", name)));
+        }
+        let (key, mut span, _) = self::name(input, "attribute")?;
+        let value = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            let (value, end) = value(input)?;
+            span.end = end;
+            Some(value)
+        } else {
+            None
+        };
+        attributes.push((key.clone(), value));
+        attribute_spans.push((key, span));
+    };
+
+    let void = VOID_ELEMENTS.contains(&name.to_lowercase().as_str());
+    let children = if closed || void {
+        Vec::new()
+    } else {
+        let children = nodes(input)?;
+        if input.is_empty() {
+            return Err(tag.syn_error(&format!("`<{}>` is never closed", name)));
+        }
+        input.parse::<Token![<]>()?;
+        input.parse::<Token![/]>()?;
+        let (close, close_span, _) = self::name(input, "element")?;
+        if close != name {
+            return Err(
+                close_span.syn_error(&format!("expected `</{}>`, found `</{}>`", name, close))
+            );
+        }
+        input.parse::<Token![>]>()?;
+        children
+    };
+
+    Ok(Element {
+        name,
+        path,
+        attributes,
+        children,
+        spans: TagSpans {
+            tag,
+            attributes: attribute_spans,
+        },
+    })
+}
+
+/// an element or attribute name. the parts of names like `data-index`, `we_on:click` and
+/// `crate::Counter` are separate tokens in rust and are joined.
+fn name(input: ParseStream, what: &str) -> syn::Result<(String, SpanRange, TokenStream)> {
+    let first = input
+        .call(Ident::parse_any)
+        .map_err(|e| syn::Error::new(e.span(), format!("expected {} name", what)))?;
+    let mut name = first.to_string();
+    let mut span = SpanRange::new(first.span(), first.span());
+    let mut tokens = TokenStream::new();
+    tokens.extend(Some(TokenTree::Ident(first)));
+    loop {
+        if input.peek(Token![::]) {
+            let colons: Token![::] = input.parse()?;
+            tokens.extend(quote::quote! { #colons });
+            name.push_str("::");
+        } else if input.peek(Token![-]) {
+            input.parse::<Token![-]>()?;
+            name.push('-');
+        } else if input.peek(Token![:]) {
+            input.parse::<Token![:]>()?;
+            name.push(':');
+        } else {
+            break;
+        }
+        // parts after a `-` can start with a number, like `data-1`
+        let part = match input.parse::<TokenTree>() {
+            Ok(TokenTree::Ident(ident)) => ident,
+            Ok(TokenTree::Literal(lit)) if name.ends_with('-') => {
+                name.push_str(&lit.to_string());
+                span.end = lit.span();
+                tokens.extend(Some(TokenTree::Literal(lit)));
+                continue;
+            }
+            Ok(token) => {
+                return Err(syn::Error::new(
+                    token.span(),
+                    format!("expected the rest of the {} name", what),
+                ))
+            }
+            Err(e) => return Err(e),
+        };
+        name.push_str(&part.to_string());
+        span.end = part.span();
+        tokens.extend(Some(TokenTree::Ident(part)));
+    }
+    Ok((name, span, tokens))
+}
+
+/// the value of an attribute and the span of its last token. string literals are unquoted and
+/// numbers, words and paths are used as written.
+fn value(input: ParseStream) -> syn::Result<(Value, proc_macro2::Span)> {
+    let token: TokenTree = input
+        .parse()
+        .map_err(|e| syn::Error::new(e.span(), "expected an attribute value"))?;
+    let span = token.span();
+    let value = match token {
+        TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => {
+            Value::Expr(group.stream())
+        }
+        TokenTree::Literal(_) => Value::Text(literal(token.clone()), token.into()),
+        TokenTree::Ident(ident) => {
+            // paths like `Self::clicked` can be used without quotes
+            let mut path = ident.to_string();
+            let mut tokens = TokenStream::from(TokenTree::Ident(ident));
+            let mut end = span;
+            while input.peek(Token![::]) {
+                let colons: Token![::] = input.parse()?;
+                let part = input.call(Ident::parse_any)?;
+                path.push_str("::");
+                path.push_str(&part.to_string());
+                end = part.span();
+                tokens.extend(quote::quote! { #colons #part });
+            }
+            return Ok((Value::Text(path, tokens), end));
+        }
+        TokenTree::Punct(p) if p.as_char() == '-' => match input.parse::<TokenTree>()? {
+            TokenTree::Literal(lit) => {
+                let text = format!("-{}", lit);
+                let end = lit.span();
+                let tokens = vec![TokenTree::Punct(p), TokenTree::Literal(lit)];
+                return Ok((Value::Text(text, tokens.into_iter().collect()), end));
+            }
+            token => return Err(syn::Error::new(token.span(), "expected a number")),
+        },
+        _ => return Err(syn::Error::new(span, "expected an attribute value")),
+    };
+    Ok((value, span))
+}
+
+/// the text of a string literal, other literals as written
+fn literal(token: TokenTree) -> String {
+    match syn::parse2::<LitStr>(token.clone().into()) {
+        Ok(lit) => lit.value(),
+        Err(_) => token.to_string(),
+    }
+}
+
+/// the text up to the next tag. rust doesn't tell where the whitespace in the template was, so
+/// the tokens are joined with a single space like html collapses whitespace, except before
+/// punctuation like `,` and `!` and after punctuation that is joined to the next token, like the
+/// `'` in `don't`. string literals are unquoted and used exactly, without spaces around them, so
+/// they can set the whitespace of a text or hold text rust can't lex.
+fn text(input: ParseStream) -> syn::Result<Text> {
+    let mut segments = Vec::new();
+    let mut text = String::new();
+    let mut span: Option<SpanRange> = None;
+    let mut first: Option<TokenTree> = None;
+    let mut last: Option<TokenTree> = None;
+    while !input.is_empty() && !input.peek(Token![<]) {
+        let token: TokenTree = input.parse()?;
+        match &last {
+            Some(last) if spaced(last, &token) => text.push(' '),
+            Some(_) => {}
+            None => first = Some(token.clone()),
+        }
+        last = Some(token.clone());
+        span = Some(match span {
+            Some(span) => SpanRange::new(span.start, token.span()),
+            None => SpanRange::new(token.span(), token.span()),
+        });
+        match token {
+            TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => {
+                if !text.is_empty() {
+                    segments.push(Segment::Text(std::mem::take(&mut text)));
+                }
+                segments.push(Segment::Expr(group.stream()));
+            }
+            TokenTree::Literal(_) => text.push_str(&literal(token)),
+            token => text.push_str(&token.to_string()),
+        }
+    }
+    if !text.is_empty() {
+        segments.push(Segment::Text(text));
+    }
+    let first = first.expect("text has at least one token");
+    let last = last.expect("text has at least one token");
+    Ok(Text {
+        segments,
+        span: span.expect("text has at least one token"),
+        space_before: !is_string(&first) && !sticks_left(&first),
+        space_after: !is_string(&last) && !is_joint(&last),
+    })
+}
+
+/// whether there is a space between two tokens of a text
+fn spaced(before: &TokenTree, after: &TokenTree) -> bool {
+    !is_string(before) && !is_string(after) && !is_joint(before) && !sticks_left(after)
+}
+
+fn is_string(token: &TokenTree) -> bool {
+    syn::parse2::<LitStr>(token.clone().into()).is_ok()
+}
+
+fn is_joint(token: &TokenTree) -> bool {
+    matches!(token, TokenTree::Punct(p) if p.spacing() == Spacing::Joint)
+}
+
+/// punctuation that is written right after the text before it
+fn sticks_left(token: &TokenTree) -> bool {
+    matches!(token, TokenTree::Punct(p) if ".,;:!?'%".contains(p.as_char()))
+}
+
+/// skips a `<!-- comment -->`
+fn comment(input: ParseStream) -> syn::Result<()> {
+    let open: Token![<] = input.parse()?;
+    input.parse::<Token![!]>()?;
+    input.parse::<Token![-]>()?;
+    input.parse::<Token![-]>()?;
+    let mut dashes = 0;
+    loop {
+        if input.is_empty() {
+            return Err(syn::Error::new(open.span, "comment is never closed"));
+        }
+        match input.parse::<TokenTree>()? {
+            TokenTree::Punct(p) if p.as_char() == '-' => dashes += 1,
+            TokenTree::Punct(p) if p.as_char() == '>' && dashes >= 2 => return Ok(()),
+            _ => dashes = 0,
+        }
+    }
+}
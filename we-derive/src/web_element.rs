@@ -0,0 +1,77 @@
+//! `#[derive(WebElement)]`, the lifecycle hooks are methods set with `#[we(..)]`.
+//!
+//! ```ignore
+//! #[we_builder(<div>{props}</div>)]
+//! #[derive(Debug, Clone, WebElement)]
+//! #[we(init = "Self::setup", mounted = "Self::start", drop = "Self::stop")]
+//! struct Clock {}
+//! ```
+
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::{spanned::Spanned, DeriveInput};
+
+use crate::options;
+
+/// the keys of `#[we(..)]` and the `WebElement` methods they set
+const HOOKS: &[(&str, &str)] = &[
+    ("init", "init"),
+    ("mounted", "mounted"),
+    ("before_unmount", "before_unmount"),
+    ("unmounted", "unmounted"),
+    ("updated", "updated"),
+    ("drop", "dropped"),
+];
+
+pub(crate) fn derive(ast: DeriveInput) -> syn::Result<TokenStream> {
+    let ident = &ast.ident;
+    let mut hooks: Vec<(&str, syn::Path)> = Vec::new();
+    let keys: Vec<&str> = HOOKS.iter().map(|(key, _)| *key).collect();
+    for (key, value) in options::parse(&ast.attrs, "we", &keys, &[])? {
+        let key_name = key.to_string();
+        let method = HOOKS
+            .iter()
+            .find(|(k, _)| *k == key_name)
+            .map(|(_, method)| *method)
+            .expect("options only yields the given keys");
+        if hooks.iter().any(|(m, _)| *m == method) {
+            return Err(syn::Error::new(
+                key.span(),
+                format!("`{}` is set more than once", key_name),
+            ));
+        }
+        hooks.push((method, value.parse()?));
+    }
+
+    let init = match hooks.iter().find(|(m, _)| *m == "init") {
+        // the function is assigned to a pointer so a wrong signature is an error at the path
+        Some((_, path)) => quote_spanned! { path.span()=>
+            let init: fn(&mut Self) -> webelements::Result<()> = #path;
+            init(self)
+        },
+        None => quote! { Ok(()) },
+    };
+    let hooks = hooks
+        .iter()
+        .filter(|(m, _)| *m != "init")
+        .map(|(method, path)| {
+            let method = syn::Ident::new(method, path.span());
+            quote_spanned! { path.span()=>
+                fn #method(&self) {
+                    let #method: fn(&Self) = #path;
+                    #method(self)
+                }
+            }
+        });
+    let (impl_generics, ty_generics, where_clause) = ast.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics webelements::WebElement for #ident #ty_generics #where_clause {
+            fn init(&mut self) -> webelements::Result<()> {
+                #init
+            }
+
+            #( #hooks )*
+        }
+    })
+}
@@ -0,0 +1,271 @@
+//! Elements that are only in the dom while a condition holds, declared in templates with
+//! `we_if` and `we_else`.
+
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    element::{anchor, as_node, elem::ElemTy, RawElement, RawNode},
+    hydrate::Hydrator,
+    lifecycle,
+    reactive::{Memo, Observable, Signal, Subscription},
+    Element, Result,
+};
+
+/// A condition a [`Conditional`] can be shown by. Conditionals shown by a signal or memo are
+/// updated when it changes.
+pub trait Condition {
+    fn value(&self) -> bool;
+
+    /// calls `f` with the new value every time it changes, `None` if it can't change
+    fn subscribe_value(&self, f: impl FnMut(bool) + 'static) -> Option<Subscription>;
+}
+
+impl Condition for bool {
+    fn value(&self) -> bool {
+        *self
+    }
+
+    fn subscribe_value(&self, _f: impl FnMut(bool) + 'static) -> Option<Subscription> {
+        None
+    }
+}
+
+impl Condition for Signal<bool> {
+    fn value(&self) -> bool {
+        self.get()
+    }
+
+    fn subscribe_value(&self, mut f: impl FnMut(bool) + 'static) -> Option<Subscription> {
+        Some(self.subscribe(move |value| f(*value)))
+    }
+}
+
+impl Condition for Memo<bool> {
+    fn value(&self) -> bool {
+        self.get()
+    }
+
+    fn subscribe_value(&self, mut f: impl FnMut(bool) + 'static) -> Option<Subscription> {
+        Some(self.subscribe(move |value| f(*value)))
+    }
+}
+
+/// The inverse of a condition, used for `we_else`.
+#[derive(Debug)]
+pub struct Not<'a, S: ?Sized>(pub &'a S);
+
+impl<S: Condition + ?Sized> Condition for Not<'_, S> {
+    fn value(&self) -> bool {
+        !self.0.value()
+    }
+
+    fn subscribe_value(&self, mut f: impl FnMut(bool) + 'static) -> Option<Subscription> {
+        self.0.subscribe_value(move |value| f(!value))
+    }
+}
+
+type BuildFn<C> = Box<dyn Fn() -> Result<C>>;
+
+struct ConditionalInner<C> {
+    parent: RawElement,
+    anchor: RawNode,
+    build: BuildFn<C>,
+    node: fn(&C) -> &RawElement,
+    child: Option<C>,
+    shown: bool,
+}
+
+/// An element that can be added to and removed from its parent. It is built the first time it
+/// is shown and kept when it is hidden, so it keeps its state and its listeners until the last
+/// handle to the conditional is dropped. Cloning a conditional gives a new handle to the same conditional.
+pub struct Conditional<C> {
+    inner: Rc<RefCell<ConditionalInner<C>>>,
+}
+
+impl<C> Clone for Conditional<C> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<C> std::fmt::Debug for Conditional<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Conditional")
+            .field("shown", &self.inner.borrow().shown)
+            .finish()
+    }
+}
+
+impl<C: 'static> Conditional<C> {
+    /// a hidden element at the end of `parent` that is created with `build` when shown
+    pub fn new<P: ElemTy, E: ElemTy>(
+        parent: &Element<P>,
+        build: impl Fn() -> Result<C> + 'static,
+    ) -> Result<Self>
+    where
+        C: AsRef<Element<E>>,
+    {
+        let anchor = anchor()?;
+        parent.dom_element().append_child(&anchor)?;
+        Ok(Self::with_parts(
+            parent.dom_element().clone(),
+            anchor,
+            Box::new(build),
+            None,
+        ))
+    }
+
+    /// an element at the end of `parent` that is shown while `condition` is true, if the
+    /// condition is a signal the element is updated for as long as `parent` lives.
+    pub fn from_source<P: ElemTy, E: ElemTy, S: Condition + ?Sized>(
+        parent: &Element<P>,
+        condition: &S,
+        build: impl Fn() -> Result<C> + 'static,
+    ) -> Result<Self>
+    where
+        C: AsRef<Element<E>>,
+    {
+        let conditional = Self::new(parent, build)?;
+        conditional.set(condition.value())?;
+        conditional.follow(parent, condition);
+        Ok(conditional)
+    }
+
+    /// a conditional for an element that is already in the dom. the element was rendered if
+    /// `condition` is true, it is taken from the dom with `hydrate` then. after that it is
+    /// shown and hidden like with [`from_source`](Self::from_source).
+    pub fn hydrate<P: ElemTy, E: ElemTy, S: Condition + ?Sized>(
+        parent: &Element<P>,
+        cursor: &mut Hydrator,
+        condition: &S,
+        build: impl Fn() -> Result<C> + 'static,
+        hydrate: impl FnOnce(&mut Hydrator) -> Result<C>,
+    ) -> Result<Self>
+    where
+        C: AsRef<Element<E>>,
+    {
+        let child = if condition.value() {
+            Some(hydrate(cursor)?)
+        } else {
+            None
+        };
+        let anchor = anchor()?;
+        let parent_node = cursor.insert(&anchor)?;
+        let conditional = Self::with_parts(parent_node, anchor, Box::new(build), child);
+        conditional.follow(parent, condition);
+        Ok(conditional)
+    }
+
+    /// shows the element while `condition` is true, for as long as `parent` lives
+    fn follow<P: ElemTy, S: Condition + ?Sized>(&self, parent: &Element<P>, condition: &S) {
+        let updated = self.clone();
+        // errors can't be returned from a subscriber, the element is left as it was
+        if let Some(subscription) = condition.subscribe_value(move |show| {
+            let _ = updated.set(show);
+        }) {
+            parent.keep(subscription);
+        }
+    }
+
+    fn with_parts<E: ElemTy>(
+        parent: RawElement,
+        anchor: RawNode,
+        build: BuildFn<C>,
+        child: Option<C>,
+    ) -> Self
+    where
+        C: AsRef<Element<E>>,
+    {
+        Self {
+            inner: Rc::new(RefCell::new(ConditionalInner {
+                parent,
+                anchor,
+                build,
+                node: |c| c.as_ref().dom_element(),
+                shown: child.is_some(),
+                child,
+            })),
+        }
+    }
+
+    /// shows or hides the element
+    pub fn set(&self, show: bool) -> Result<()> {
+        if show {
+            self.show()
+        } else {
+            self.hide()
+        }
+    }
+
+    /// adds the element to the dom, building it if it was never shown
+    pub fn show(&self) -> Result<()> {
+        // the conditional is not borrowed anymore, the hooks can use it
+        if let Some(node) = self.show_child()? {
+            lifecycle::check(&node);
+        }
+        Ok(())
+    }
+
+    /// adds the element to the dom, the added node if it was hidden
+    fn show_child(&self) -> Result<Option<RawNode>> {
+        let mut inner = self.inner.borrow_mut();
+        let inner = &mut *inner;
+        if inner.shown {
+            return Ok(None);
+        }
+        if inner.child.is_none() {
+            inner.child = Some((inner.build)()?);
+        }
+        let child = inner.child.as_ref().expect("child is built");
+        let node = as_node((inner.node)(child));
+        inner.parent.insert_before(node, Some(&inner.anchor))?;
+        inner.shown = true;
+        Ok(Some(node.clone()))
+    }
+
+    /// removes the element from the dom
+    pub fn hide(&self) -> Result<()> {
+        let node = {
+            let mut inner = self.inner.borrow_mut();
+            if !inner.shown {
+                return Ok(());
+            }
+            match inner.child.as_ref() {
+                Some(child) => as_node((inner.node)(child)).clone(),
+                None => {
+                    inner.shown = false;
+                    return Ok(());
+                }
+            }
+        };
+        // the conditional is not borrowed while the unmount hooks run, they can use it
+        lifecycle::before_remove(&node);
+        {
+            let mut inner = self.inner.borrow_mut();
+            // a hook may have hidden the element already
+            if !inner.shown {
+                return Ok(());
+            }
+            // the element is kept to be shown again, with its listeners
+            inner.parent.remove_child(&node)?;
+            inner.shown = false;
+        }
+        lifecycle::check(&node);
+        Ok(())
+    }
+
+    pub fn is_shown(&self) -> bool {
+        self.inner.borrow().shown
+    }
+
+    /// the element if it is shown
+    pub fn get(&self) -> Option<C>
+    where
+        C: Clone,
+    {
+        let inner = self.inner.borrow();
+        inner.child.clone().filter(|_| inner.shown)
+    }
+}
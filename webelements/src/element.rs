@@ -1,18 +1,157 @@
-use std::str::FromStr;
+use std::{cell::RefCell, fmt::Display, rc::Rc, str::FromStr};
 
 use elem::ElemTy;
-use wasm_bindgen::{prelude::Closure, JsCast};
-use web_sys::{HtmlButtonElement, HtmlInputElement, InputEvent};
+use wasm_bindgen::JsCast;
+use web_sys::InputEvent;
 
-use crate::{Error, Result};
+use crate::{
+    event::{self, Event, ListenerHandle, ListenerOptions},
+    hydrate::Hydrator,
+    reactive::{Observable, Subscription},
+    sanitize::Policy,
+    Context, Error, Result, TextNode,
+};
 
 pub use web_sys::MouseEvent;
 
+/// the node type that backs an [`Element`], a live dom element or a node in the in-memory dom
+/// when the `ssr` feature is enabled.
+#[cfg(not(feature = "ssr"))]
+pub type RawElement = web_sys::Element;
+#[cfg(feature = "ssr")]
+pub type RawElement = crate::ssr::Node;
+
+#[cfg(not(feature = "ssr"))]
+pub(crate) type RawNode = web_sys::Node;
+#[cfg(feature = "ssr")]
+pub(crate) type RawNode = crate::ssr::Node;
+
+#[cfg(not(feature = "ssr"))]
+pub(crate) fn as_node(element: &RawElement) -> &RawNode {
+    element.as_ref()
+}
+
+#[cfg(feature = "ssr")]
+pub(crate) fn as_node(element: &RawElement) -> &RawNode {
+    element
+}
+
+/// calls the mount hooks of the components in `child` after it was added to `parent`
+fn mounted(parent: &RawNode, child: &RawNode) {
+    if parent.is_connected() {
+        crate::lifecycle::check(child);
+    }
+}
+
+/// removes `child` from `parent`, calling the unmount hooks
+fn remove(parent: &RawNode, child: &RawNode) -> Result<()> {
+    if child.parent_node().as_ref() != Some(parent) {
+        return Err(Error::NotFound);
+    }
+    crate::lifecycle::before_remove(child);
+    parent.remove_child(child)?;
+    crate::lifecycle::check(child);
+    Ok(())
+}
+
+/// the element found by a query as a `E`
+pub(crate) fn query_one<E: ElemTy>(
+    found: std::result::Result<Option<RawElement>, impl Into<Error>>,
+    selector: &str,
+) -> Result<Option<Element<E>>> {
+    let context = || format!("querying `{}`", selector);
+    found
+        .with_context(context)?
+        .map(|element| AnyElement::from_dom_element(element).try_into_typed())
+        .transpose()
+        .with_context(context)
+}
+
+/// the elements found by a query as `E`s
+#[cfg(not(feature = "ssr"))]
+pub(crate) fn query_all<E: ElemTy>(
+    found: std::result::Result<web_sys::NodeList, wasm_bindgen::JsValue>,
+    selector: &str,
+) -> Result<Vec<Element<E>>> {
+    let context = || format!("querying `{}`", selector);
+    let found = found.with_context(context)?;
+    (0..found.length())
+        .filter_map(|i| found.item(i))
+        .map(|node| {
+            let element = node
+                .dyn_into::<RawElement>()
+                .map_err(|_| Error::Cast(std::any::type_name::<RawElement>()))?;
+            AnyElement::from_dom_element(element).try_into_typed()
+        })
+        .collect::<Result<_>>()
+        .with_context(context)
+}
+
+#[cfg(feature = "ssr")]
+pub(crate) fn query_all<E: ElemTy>(
+    found: Result<Vec<RawElement>>,
+    selector: &str,
+) -> Result<Vec<Element<E>>> {
+    let context = || format!("querying `{}`", selector);
+    found
+        .with_context(context)?
+        .into_iter()
+        .map(|element| AnyElement::from_dom_element(element).try_into_typed())
+        .collect::<Result<_>>()
+        .with_context(context)
+}
+
+/// a selector for the element with the id `id`, it is quoted so it can be any string
+pub(crate) fn id_selector(id: &str) -> String {
+    format!("[id=\"{}\"]", id.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(not(feature = "ssr"))]
+fn raw_children(element: &RawElement) -> Vec<RawElement> {
+    let children = element.children();
+    (0..children.length())
+        .filter_map(|i| children.item(i))
+        .collect()
+}
+
+#[cfg(feature = "ssr")]
+fn raw_children(element: &RawElement) -> Vec<RawElement> {
+    element.children()
+}
+
+pub(crate) const XHTML_NAMESPACE: &str = "http://www.w3.org/1999/xhtml";
+
+/// whether `element` is an html element and not one of svg or mathml, elements without a
+/// namespace on the server are html
+// `Option::is_none_or` needs a newer compiler than the crate does
+#[allow(clippy::unnecessary_map_or)]
+fn is_html(element: &RawElement) -> bool {
+    element
+        .namespace_uri()
+        .map_or(true, |ns| ns == XHTML_NAMESPACE)
+}
+
+/// an empty text node that marks a position among the children of an element, used to insert
+/// elements that are added later in the right place
+#[cfg(not(feature = "ssr"))]
+pub(crate) fn anchor() -> Result<RawNode> {
+    Ok(crate::document()?.create_text_node("").into())
+}
+
+#[cfg(feature = "ssr")]
+pub(crate) fn anchor() -> Result<RawNode> {
+    Ok(RawNode::text(""))
+}
+
 pub mod elem {
     use wasm_bindgen::JsCast;
     use we_derive::element_types;
-    pub trait ElemTy {
-        type Elem: AsRef<web_sys::Element>;
+    pub trait ElemTy: 'static {
+        type Elem: AsRef<web_sys::Element> + JsCast + Clone;
+        /// tag name the element is created with
+        const TAG: &'static str;
+        /// namespace uri for elements that are not html, like svg and mathml
+        const NAMESPACE: Option<&'static str>;
         fn make() -> crate::Result<Self::Elem>;
     }
     element_types!();
@@ -20,13 +159,92 @@ pub mod elem {
 
 pub trait WebElementBuilder {
     type Elem: ElemTy;
+    /// parameters the element is build with, set in the template with `we_props`
+    type Props;
+
+    fn build_with(props: Self::Props) -> Result<Self>
+    where
+        Self: std::marker::Sized;
+
     fn build() -> Result<Self>
+    where
+        Self: std::marker::Sized,
+        Self::Props: Default,
+    {
+        Self::build_with(Default::default())
+    }
+
+    /// takes the elements of the template from an existing dom instead of creating them, the
+    /// signals in `props` are bound to the elements like they are by `build_with`
+    fn hydrate_from(cursor: &mut Hydrator, props: Self::Props) -> Result<Self>
+    where
+        Self: std::marker::Sized;
+
+    /// attaches to markup that is already in the dom, `root` is matched to the root of the template
+    fn hydrate_with(root: &RawElement, props: Self::Props) -> Result<Self>
+    where
+        Self: std::marker::Sized,
+    {
+        let mut cursor = Hydrator::new(root);
+        let element = Self::hydrate_from(&mut cursor, props)?;
+        cursor.finish()?;
+        Ok(element)
+    }
+
+    fn hydrate(root: &RawElement) -> Result<Self>
+    where
+        Self: std::marker::Sized,
+        Self::Props: Default,
+    {
+        Self::hydrate_with(root, Default::default())
+    }
+
+    /// a handle to the same elements that does not keep their bindings alive, the lifecycle
+    /// hooks are called on it
+    fn unbound(&self) -> Self
     where
         Self: std::marker::Sized;
 }
 
+/// An element made from a template. The lifecycle hooks are called on a handle to the same
+/// elements, see [`WebElementBuilder::unbound`].
 pub trait WebElement: WebElementBuilder {
+    /// called after the element is built or hydrated
     fn init(&mut self) -> Result<()>;
+
+    /// called after the element is added to the document
+    fn mounted(&self) {}
+
+    /// called before the element is removed from the document with the methods of this crate,
+    /// not when it is removed some other way
+    fn before_unmount(&self) {}
+
+    /// called after the element is removed from the document
+    fn unmounted(&self) {}
+
+    /// called after a signal bound to the element or one of its children changed it, for
+    /// children that are not an element of their own
+    fn updated(&self) {}
+
+    /// called when the last handle to the element is dropped
+    fn dropped(&self) {}
+}
+
+/// what is kept alive by the clones of an element
+#[derive(Debug, Default)]
+struct Bindings {
+    subscriptions: RefCell<Vec<Subscription>>,
+    listeners: RefCell<Vec<ListenerHandle>>,
+    /// the ids of the lifecycle registrations with this element as root
+    components: RefCell<Vec<usize>>,
+}
+
+impl Drop for Bindings {
+    fn drop(&mut self) {
+        for id in self.components.get_mut().drain(..) {
+            crate::lifecycle::unregister(id);
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -34,7 +252,15 @@ pub struct Element<E>
 where
     E: ElemTy,
 {
+    #[cfg(not(feature = "ssr"))]
     element: E::Elem,
+    #[cfg(feature = "ssr")]
+    element: RawElement,
+    #[cfg(feature = "ssr")]
+    ty: std::marker::PhantomData<E>,
+    /// subscriptions of the signals bound to this element, its listeners and its lifecycle
+    /// registrations, shared between clones and removed when the last clone is dropped
+    bindings: Rc<Bindings>,
 }
 
 impl<E> AsRef<Element<E>> for Element<E>
@@ -46,31 +272,149 @@ where
     }
 }
 
+/// An element of any type, for lists of elements of different types. It is turned back into
+/// an [`Element`] with [`try_into_typed`](AnyElement::try_into_typed), the bindings of the
+/// element are kept in between.
+#[derive(Debug, Clone)]
+pub struct AnyElement {
+    element: RawElement,
+    bindings: Rc<Bindings>,
+}
+
+impl AnyElement {
+    pub fn from_dom_element(element: RawElement) -> AnyElement {
+        AnyElement {
+            element,
+            bindings: Rc::default(),
+        }
+    }
+
+    pub fn dom_element(&self) -> &RawElement {
+        &self.element
+    }
+
+    /// the tag name in lower case, like `E::TAG` for html elements
+    pub fn tag(&self) -> String {
+        let tag = self.element.tag_name();
+        match self.element.namespace_uri() {
+            Some(ns) if ns != XHTML_NAMESPACE => tag,
+            _ => tag.to_ascii_lowercase(),
+        }
+    }
+
+    /// whether the element is a `E`. elements are matched by their tag, `elem::Base` matches
+    /// any html element.
+    pub fn is<E: ElemTy>(&self) -> bool {
+        let any_html = std::any::TypeId::of::<E>() == std::any::TypeId::of::<elem::Base>();
+        self.is_instance::<E>()
+            && (any_html || self.element.tag_name().eq_ignore_ascii_case(E::TAG))
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    fn is_instance<E: ElemTy>(&self) -> bool {
+        self.element.has_type::<E::Elem>()
+    }
+
+    #[cfg(feature = "ssr")]
+    fn is_instance<E: ElemTy>(&self) -> bool {
+        self.element.namespace_uri().as_deref() == E::NAMESPACE
+    }
+
+    /// the element as a `E`, or `Error::Cast` when it is another element
+    pub fn try_into_typed<E: ElemTy>(self) -> Result<Element<E>> {
+        if !self.is::<E>() {
+            return Err(Error::Cast(std::any::type_name::<E::Elem>()))
+                .with_context(|| format!("casting `<{}>` to `<{}>`", self.tag(), E::TAG));
+        }
+        Ok(Element {
+            #[cfg(not(feature = "ssr"))]
+            element: self.element.unchecked_into(),
+            #[cfg(feature = "ssr")]
+            element: self.element,
+            #[cfg(feature = "ssr")]
+            ty: std::marker::PhantomData,
+            bindings: self.bindings,
+        })
+    }
+}
+
+impl<E: ElemTy> From<Element<E>> for AnyElement {
+    fn from(element: Element<E>) -> Self {
+        AnyElement {
+            element: element.as_element().clone(),
+            bindings: element.bindings,
+        }
+    }
+}
+
 impl<E> Element<E>
 where
     E: ElemTy,
 {
+    #[cfg(not(feature = "ssr"))]
     pub fn new() -> Result<Element<E>> {
-        let element = E::make()?;
-        Ok(Self { element })
+        let element = E::make().with_context(|| format!("creating `<{}>`", E::TAG))?;
+        Ok(Self::from_element(element))
     }
 
+    #[cfg(feature = "ssr")]
+    pub fn new() -> Result<Element<E>> {
+        Ok(Self {
+            element: RawElement::element(E::TAG, E::NAMESPACE),
+            ty: std::marker::PhantomData,
+            bindings: Rc::default(),
+        })
+    }
+
+    #[cfg(not(feature = "ssr"))]
     pub fn from_element(element: E::Elem) -> Self {
-        Self { element }
+        Self {
+            element,
+            bindings: Rc::default(),
+        }
     }
 
-    fn as_element(&self) -> &web_sys::Element {
-        self.element.as_ref()
+    #[cfg(not(feature = "ssr"))]
+    pub(crate) fn from_raw(element: RawElement) -> Result<Self> {
+        let element = element
+            .dyn_into::<E::Elem>()
+            .map_err(|_| Error::Cast(std::any::type_name::<E::Elem>()))?;
+        Ok(Self::from_element(element))
     }
 
-    fn as_node(&self) -> &web_sys::Node {
+    #[cfg(feature = "ssr")]
+    pub(crate) fn from_raw(element: RawElement) -> Result<Self> {
+        Ok(Self {
+            element,
+            ty: std::marker::PhantomData,
+            bindings: Rc::default(),
+        })
+    }
+
+    /// the dom element this wraps, a `web_sys::Element` or with the `ssr` feature a node in
+    /// the in-memory dom
+    pub fn dom_element(&self) -> &RawElement {
+        self.as_element()
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    fn as_element(&self) -> &RawElement {
         self.element.as_ref()
     }
 
+    #[cfg(feature = "ssr")]
+    fn as_element(&self) -> &RawElement {
+        &self.element
+    }
+
     pub fn append<T: ElemTy>(&self, other: impl AsRef<Element<T>>) -> Result<()> {
-        self.element
-            .as_ref()
-            .append_child(other.as_ref().as_node())?;
+        self.as_element()
+            .append_child(other.as_ref().as_element())
+            .with_context(|| format!("appending `<{}>` to `<{}>`", T::TAG, E::TAG))?;
+        mounted(
+            as_node(self.as_element()),
+            as_node(other.as_ref().as_element()),
+        );
         Ok(())
     }
 
@@ -81,12 +425,187 @@ where
         items.into_iter().try_for_each(|i| self.append(i))
     }
 
+    /// inserts `other` before the first child of the element
+    pub fn prepend<T: ElemTy>(&self, other: impl AsRef<Element<T>>) -> Result<()> {
+        let first = as_node(self.as_element()).first_child();
+        self.as_element()
+            .insert_before(as_node(other.as_ref().as_element()), first.as_ref())
+            .with_context(|| format!("prepending `<{}>` to `<{}>`", T::TAG, E::TAG))?;
+        mounted(
+            as_node(self.as_element()),
+            as_node(other.as_ref().as_element()),
+        );
+        Ok(())
+    }
+
+    /// inserts `other` in the element before its child `reference`
+    pub fn insert_before<T: ElemTy, R: ElemTy>(
+        &self,
+        other: impl AsRef<Element<T>>,
+        reference: impl AsRef<Element<R>>,
+    ) -> Result<()> {
+        self.as_element()
+            .insert_before(
+                as_node(other.as_ref().as_element()),
+                Some(as_node(reference.as_ref().as_element())),
+            )
+            .with_context(|| {
+                format!(
+                    "inserting `<{}>` before `<{}>` in `<{}>`",
+                    T::TAG,
+                    R::TAG,
+                    E::TAG
+                )
+            })?;
+        mounted(
+            as_node(self.as_element()),
+            as_node(other.as_ref().as_element()),
+        );
+        Ok(())
+    }
+
+    /// inserts `other` in the element after its child `reference`
+    pub fn insert_after<T: ElemTy, R: ElemTy>(
+        &self,
+        other: impl AsRef<Element<T>>,
+        reference: impl AsRef<Element<R>>,
+    ) -> Result<()> {
+        let reference = as_node(reference.as_ref().as_element());
+        if reference.parent_node().as_ref() != Some(as_node(self.as_element())) {
+            return Err(Error::NotFound).with_context(|| {
+                format!(
+                    "inserting `<{}>` after `<{}>` in `<{}>`",
+                    T::TAG,
+                    R::TAG,
+                    E::TAG
+                )
+            });
+        }
+        self.as_element()
+            .insert_before(
+                as_node(other.as_ref().as_element()),
+                reference.next_sibling().as_ref(),
+            )
+            .with_context(|| {
+                format!(
+                    "inserting `<{}>` after `<{}>` in `<{}>`",
+                    T::TAG,
+                    R::TAG,
+                    E::TAG
+                )
+            })?;
+        mounted(
+            as_node(self.as_element()),
+            as_node(other.as_ref().as_element()),
+        );
+        Ok(())
+    }
+
+    /// removes the element from its parent. the unmount hooks of the components in it are
+    /// called, elements that are moved somewhere else should be inserted there instead.
+    pub fn remove(&self) -> Result<()> {
+        let node = as_node(self.as_element());
+        match node.parent_node() {
+            Some(parent) => remove(&parent, node)
+                .with_context(|| format!("removing `<{}>` from its parent", E::TAG)),
+            None => Ok(()),
+        }
+    }
+
+    /// removes the child `child` from the element, like [`remove`](Self::remove)
+    pub fn remove_child<T: ElemTy>(&self, child: impl AsRef<Element<T>>) -> Result<()> {
+        remove(
+            as_node(self.as_element()),
+            as_node(child.as_ref().as_element()),
+        )
+        .with_context(|| format!("removing `<{}>` from `<{}>`", T::TAG, E::TAG))
+    }
+
+    /// puts `other` in the place of the element, which is removed like with
+    /// [`remove`](Self::remove)
+    pub fn replace_with<T: ElemTy>(&self, other: impl AsRef<Element<T>>) -> Result<()> {
+        let node = as_node(self.as_element());
+        let context = || format!("replacing `<{}>` with `<{}>`", E::TAG, T::TAG);
+        let parent = node
+            .parent_node()
+            .ok_or(Error::NotFound)
+            .with_context(context)?;
+        let other = as_node(other.as_ref().as_element());
+        parent
+            .insert_before(other, Some(node))
+            .with_context(context)?;
+        remove(&parent, node).with_context(context)?;
+        mounted(&parent, other);
+        Ok(())
+    }
+
+    /// removes all children of the element, text included, like [`remove`](Self::remove)
+    pub fn clear_children(&self) -> Result<()> {
+        let node = as_node(self.as_element());
+        while let Some(child) = node.first_child() {
+            remove(node, &child)
+                .with_context(|| format!("removing the children of `<{}>`", E::TAG))?;
+        }
+        Ok(())
+    }
+
+    /// the child elements, elements that are not html elements like `<svg>` are skipped
+    pub fn children(&self) -> impl Iterator<Item = Element<elem::Base>> {
+        raw_children(self.as_element())
+            .into_iter()
+            .filter(is_html)
+            .filter_map(|child| Element::from_raw(child).ok())
+    }
+
+    /// the child elements that are a `T`
+    pub fn children_as<T: ElemTy>(&self) -> impl Iterator<Item = Element<T>> {
+        raw_children(self.as_element())
+            .into_iter()
+            .filter_map(|child| AnyElement::from_dom_element(child).try_into_typed().ok())
+    }
+
+    /// the first element in the subtree of the element that matches `selector`, `Error::Cast`
+    /// when it is not a `T`
+    pub fn query<T: ElemTy>(&self, selector: &str) -> Result<Option<Element<T>>> {
+        query_one(self.as_element().query_selector(selector), selector)
+    }
+
+    /// all elements in the subtree of the element that match `selector`, `Error::Cast` when
+    /// one of them is not a `T`
+    pub fn query_all<T: ElemTy>(&self, selector: &str) -> Result<Vec<Element<T>>> {
+        query_all(self.as_element().query_selector_all(selector), selector)
+    }
+
+    /// the element with the id `id` in the subtree of the element
+    pub fn by_id<T: ElemTy>(&self, id: &str) -> Result<Option<Element<T>>> {
+        self.query(&id_selector(id))
+    }
+
+    /// appends a text node after the children of the element, the node can be kept to change
+    /// its text later
+    pub fn append_text(&self, text: impl AsRef<str>) -> Result<TextNode> {
+        let text = TextNode::new(text)?;
+        self.as_element().append_child(text.as_node())?;
+        Ok(text)
+    }
+
     pub fn root(&self) -> &Element<E> {
         self
     }
 
+    // the `class` attribute is used instead of `className` because on svg and mathml
+    // elements `className` is not a string.
+    fn class_string(&self) -> String {
+        self.as_element().get_attribute("class").unwrap_or_default()
+    }
+
+    fn set_class_string(&self, class: &str) {
+        // setting the `class` attribute can only fail on an invalid attribute name
+        let _ = self.as_element().set_attribute("class", class);
+    }
+
     pub fn has_class(&self, class: impl AsRef<str>) -> bool {
-        let class_string: String = self.as_element().class_name();
+        let class_string = self.class_string();
         for class_name in class_string.split_whitespace() {
             if class.as_ref() == class_name {
                 return true;
@@ -108,25 +627,25 @@ where
     pub fn add_class(&self, class: impl AsRef<str>) {
         for class in class.as_ref().split_whitespace() {
             if !self.has_class(class) {
-                let mut class_string: String = self.as_element().class_name();
+                let mut class_string = self.class_string();
                 class_string.push_str(&format!(" {}", class));
-                self.as_element().set_class_name(class_string.trim());
+                self.set_class_string(class_string.trim());
             }
         }
     }
 
     pub fn set_class(&self, class: impl AsRef<str>) {
-        self.as_element().set_class_name(class.as_ref());
+        self.set_class_string(class.as_ref());
     }
 
     pub fn clear_class(&self) {
-        self.as_element().set_class_name("");
+        self.set_class_string("");
     }
 
     pub fn remove_class(&self, class: impl AsRef<str>) {
         for class in class.as_ref().split_whitespace() {
             if self.has_class(class) {
-                let class_string = self.as_element().class_name();
+                let class_string = self.class_string();
                 let mut new_string = Vec::<&str>::new();
                 for class_name in class_string.split_whitespace() {
                     if class_name != class {
@@ -134,23 +653,41 @@ where
                     }
                 }
                 let new_string = new_string.join(" ");
-                self.as_element().set_class_name(new_string.trim());
+                self.set_class_string(new_string.trim());
             }
         }
     }
 
+    /// sets the text of the element, the text is never read as html
     pub fn set_text(&self, text: impl AsRef<str>) {
-        self.as_element().set_inner_html(text.as_ref())
+        self.as_element().set_text_content(Some(text.as_ref()))
+    }
+
+    /// sets the content of the element to `html` as it is. the browser runs any scripts and
+    /// event handlers in it, so it should never contain input from users, use
+    /// [`set_html_sanitized`](Self::set_html_sanitized) for that.
+    pub fn set_inner_html_unchecked(&self, html: impl AsRef<str>) {
+        self.as_element().set_inner_html(html.as_ref())
+    }
+
+    /// sets the content of the element to `html` with everything `policy` does not allow removed
+    pub fn set_html_sanitized(&self, html: impl AsRef<str>, policy: &Policy) {
+        self.set_inner_html_unchecked(policy.sanitize(html.as_ref()))
     }
 
     pub fn set_attr(&self, name: impl AsRef<str>, value: impl AsRef<str>) -> Result<()> {
+        let name = name.as_ref();
         self.as_element()
-            .set_attribute(name.as_ref(), value.as_ref())?;
+            .set_attribute(name, value.as_ref())
+            .with_context(|| format!("setting attribute `{}` on `<{}>`", name, E::TAG))?;
         Ok(())
     }
 
     pub fn del_attr(&self, name: impl AsRef<str>) -> Result<()> {
-        self.as_element().remove_attribute(name.as_ref())?;
+        let name = name.as_ref();
+        self.as_element()
+            .remove_attribute(name)
+            .with_context(|| format!("removing attribute `{}` from `<{}>`", name, E::TAG))?;
         Ok(())
     }
 
@@ -158,88 +695,325 @@ where
         self.as_element().get_attribute(name.as_ref())
     }
 
-    pub fn on_click(&self, callback: impl FnMut(MouseEvent) + 'static) -> Result<()> {
-        let closure = Closure::wrap(Box::new(callback) as Box<dyn FnMut(MouseEvent)>);
-        self.as_element()
-            .add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())
-            .map_err(Error::JsError)?;
-        closure.forget();
+    /// a handle to the same dom element that does not keep the bindings of this element alive,
+    /// used inside subscriptions so they don't keep themselves alive
+    pub fn unbound(&self) -> Element<E> {
+        Element {
+            element: self.element.clone(),
+            #[cfg(feature = "ssr")]
+            ty: std::marker::PhantomData,
+            bindings: Rc::default(),
+        }
+    }
+
+    /// calls `update` with the current value of `signal` and again every time it changes, only
+    /// the error of the first update is returned.
+    fn bind<T: 'static>(
+        &self,
+        signal: &impl Observable<T>,
+        update: impl Fn(&Self, &T) -> Result<()> + 'static,
+    ) -> Result<()> {
+        let this = self.unbound();
+        signal.with(|value| update(&this, value))?;
+        let subscription = signal.subscribe(move |value| {
+            let _ = update(&this, value);
+            crate::lifecycle::updated(as_node(this.as_element()));
+        });
+        self.keep(subscription);
         Ok(())
     }
 
+    /// keeps `subscription` until the last clone of this element is dropped
+    pub(crate) fn keep(&self, subscription: Subscription) {
+        self.bindings.subscriptions.borrow_mut().push(subscription);
+    }
+
+    pub(crate) fn add_component(&self, id: usize) {
+        self.bindings.components.borrow_mut().push(id);
+    }
+
+    /// keeps the text of the element equal to the value of `signal`
+    pub fn bind_text<T: Display + 'static>(&self, signal: &impl Observable<T>) {
+        let _ = self.bind(signal, |el, value| {
+            el.set_text(value.to_string());
+            Ok(())
+        });
+    }
+
+    /// keeps the attribute `name` equal to the value of `signal`
+    pub fn bind_attr<T: Display + 'static>(
+        &self,
+        name: impl Into<String>,
+        signal: &impl Observable<T>,
+    ) -> Result<()> {
+        let name = name.into();
+        self.bind(signal, move |el, value| {
+            el.set_attr(&name, value.to_string())
+        })
+    }
+
+    /// adds `class` to the element while the value of `signal` is true
+    pub fn bind_class(&self, class: impl Into<String>, signal: &impl Observable<bool>) {
+        let class = class.into();
+        let _ = self.bind(signal, move |el, value| {
+            if *value {
+                el.add_class(&class)
+            } else {
+                el.remove_class(&class)
+            }
+            Ok(())
+        });
+    }
+
+    /// serializes the element and all its children to html
+    pub fn to_html(&self) -> String {
+        self.as_element().outer_html()
+    }
+
+    /// calls `callback` every time `Ev` is fired on the element until the returned handle is
+    /// dropped.
+    pub fn on<Ev: Event>(
+        &self,
+        callback: impl FnMut(Ev::Event) + 'static,
+    ) -> Result<ListenerHandle> {
+        self.on_with::<Ev>(ListenerOptions::default(), callback)
+    }
+
+    /// like [`on`](Self::on) with options for the listener
+    pub fn on_with<Ev: Event>(
+        &self,
+        options: ListenerOptions,
+        callback: impl FnMut(Ev::Event) + 'static,
+    ) -> Result<ListenerHandle> {
+        event::listen(self.as_element(), Ev::NAME, options, callback)
+    }
+
+    /// keeps the listener until the last clone of this element is dropped. elements found
+    /// with [`children`](Self::children) or a query are new handles that keep nothing, the
+    /// listener is removed when the handle is dropped.
+    pub fn keep_listener(&self, handle: ListenerHandle) {
+        self.bindings.listeners.borrow_mut().push(handle);
+    }
+
+    /// calls `callback` every time `event` is fired on the element until the returned handle is
+    /// dropped. the type of the event is chosen by the callback.
+    pub fn on_event<Ev>(
+        &self,
+        event: &str,
+        callback: impl FnMut(Ev) + 'static,
+    ) -> Result<ListenerHandle>
+    where
+        Ev: JsCast + 'static,
+    {
+        event::listen(
+            self.as_element(),
+            event,
+            ListenerOptions::default(),
+            callback,
+        )
+    }
+
+    pub fn on_click(&self, callback: impl FnMut(MouseEvent) + 'static) -> Result<()> {
+        self.on::<event::Click>(callback)
+            .map(|handle| self.keep_listener(handle))
+    }
+
     pub fn on_mousedown(&self, callback: impl FnMut(MouseEvent) + 'static) -> Result<()> {
-        let closure = Closure::wrap(Box::new(callback) as Box<dyn FnMut(MouseEvent)>);
-        self.as_element()
-            .add_event_listener_with_callback("mousedown", closure.as_ref().unchecked_ref())
-            .map_err(Error::JsError)?;
-        closure.forget();
-        Ok(())
+        self.on::<event::MouseDown>(callback)
+            .map(|handle| self.keep_listener(handle))
     }
 
     pub fn on_mouseenter(&self, callback: impl FnMut(MouseEvent) + 'static) -> Result<()> {
-        let closure = Closure::wrap(Box::new(callback) as Box<dyn FnMut(MouseEvent)>);
-        self.as_element()
-            .add_event_listener_with_callback("mouseenter", closure.as_ref().unchecked_ref())
-            .map_err(Error::JsError)?;
-        closure.forget();
-        Ok(())
+        self.on::<event::MouseEnter>(callback)
+            .map(|handle| self.keep_listener(handle))
     }
 
     pub fn on_mouseup(&self, callback: impl FnMut(MouseEvent) + 'static) -> Result<()> {
-        let closure = Closure::wrap(Box::new(callback) as Box<dyn FnMut(MouseEvent)>);
-        self.as_element()
-            .add_event_listener_with_callback("mouseup", closure.as_ref().unchecked_ref())
-            .map_err(Error::JsError)?;
-        closure.forget();
-        Ok(())
+        self.on::<event::MouseUp>(callback)
+            .map(|handle| self.keep_listener(handle))
+    }
+
+    /// adds or removes an attribute that is on when it is present. on the server there are no
+    /// properties, these attributes are also used for properties like `checked`.
+    fn set_bool_attr(&self, name: &str, value: bool) -> Result<()> {
+        if value {
+            self.set_attr(name, "")
+        } else {
+            self.del_attr(name)
+        }
     }
 }
 
+#[cfg(not(feature = "ssr"))]
 impl Element<elem::Base> {
     pub fn style(&self) -> web_sys::CssStyleDeclaration {
         self.element.style()
     }
 }
 
-impl Element<elem::Button> {
-    pub fn set_disabled(&self, disabled: bool) {
-        self.as_element()
-            .dyn_ref::<HtmlButtonElement>()
-            .unwrap()
-            .set_disabled(disabled)
+impl Element<elem::Input> {
+    pub fn on_input(&self, callback: impl FnMut(InputEvent) + 'static) -> Result<()> {
+        self.on::<event::Input>(callback)
+            .map(|handle| self.keep_listener(handle))
+    }
+
+    /// the current value parsed as `T`, the error keeps the value and why it could not be parsed
+    pub fn get_value<T>(&self) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: std::error::Error + Send + Sync + 'static,
+    {
+        let value = self.value();
+        value.parse::<T>().map_err(|e| Error::parse::<T>(value, e))
+    }
+
+    /// the value of a number, range or date and time input as number, `None` when it is empty
+    /// or invalid. date and time inputs give milliseconds since 1970 or midnight.
+    #[cfg(not(feature = "ssr"))]
+    pub fn value_as_number(&self) -> Option<f64> {
+        Some(self.element.value_as_number()).filter(|n| !n.is_nan())
+    }
+
+    // the server does not know dates, only the value of number and range inputs is read
+    #[cfg(feature = "ssr")]
+    pub fn value_as_number(&self) -> Option<f64> {
+        match self.attr("type").as_deref() {
+            Some("number") | Some("range") => self.value().trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// the value of a date input as date, `None` when it is empty, invalid or the input is
+    /// not a date input
+    #[cfg(not(feature = "ssr"))]
+    pub fn value_as_date(&self) -> Option<js_sys::Date> {
+        // the browser throws for inputs that have no date
+        self.element.value_as_date().ok().flatten()
+    }
+
+    /// the files selected in a file input
+    #[cfg(not(feature = "ssr"))]
+    pub fn files(&self) -> Vec<web_sys::File> {
+        match self.element.files() {
+            Some(files) => (0..files.length()).filter_map(|i| files.item(i)).collect(),
+            None => Vec::new(),
+        }
     }
 }
 
-impl Element<elem::Input> {
-    pub fn on_input(&self, callback: impl FnMut(InputEvent) + 'static) -> Result<()> {
-        let closure = Closure::wrap(Box::new(callback) as Box<dyn FnMut(InputEvent)>);
-        self.as_element()
-            .add_event_listener_with_callback("input", closure.as_ref().unchecked_ref())
-            .map_err(Error::JsError)?;
-        closure.forget();
-        Ok(())
+impl Element<elem::Textarea> {
+    /// the current text
+    #[cfg(not(feature = "ssr"))]
+    pub fn value(&self) -> String {
+        self.element.value()
     }
 
-    pub fn set_min<T: ToString>(&self, value: T) {
-        self.element.set_min(&value.to_string())
+    #[cfg(not(feature = "ssr"))]
+    pub fn set_value(&self, value: impl AsRef<str>) {
+        self.element.set_value(value.as_ref())
     }
 
-    pub fn set_max<T: ToString>(&self, value: T) {
-        self.element.set_max(&value.to_string())
+    // on the server the text is the content of the element
+    #[cfg(feature = "ssr")]
+    pub fn value(&self) -> String {
+        self.element.text_content()
     }
 
-    pub fn set_value<T: ToString>(&self, value: T) {
-        self.element.set_value(&value.to_string())
+    #[cfg(feature = "ssr")]
+    pub fn set_value(&self, value: impl AsRef<str>) {
+        self.element.set_text_content(Some(value.as_ref()))
     }
+}
 
-    pub fn get_value<T: FromStr>(&self) -> Result<T> {
-        self.element.value().parse::<T>().map_err(|_| Error::Value)
+impl Element<elem::Select> {
+    /// the value of the selected option, empty if no option is selected
+    #[cfg(not(feature = "ssr"))]
+    pub fn value(&self) -> String {
+        self.element.value()
     }
 
-    pub fn set_disabled(&self, disabled: bool) {
-        self.as_element()
-            .dyn_ref::<HtmlInputElement>()
-            .unwrap()
-            .set_disabled(disabled)
+    /// selects the first option with `value`, or no option if there is none
+    #[cfg(not(feature = "ssr"))]
+    pub fn set_value(&self, value: impl AsRef<str>) {
+        self.element.set_value(value.as_ref())
+    }
+
+    /// the index of the selected option
+    #[cfg(not(feature = "ssr"))]
+    pub fn selected_index(&self) -> Option<usize> {
+        let index = self.element.selected_index();
+        if index < 0 {
+            None
+        } else {
+            Some(index as usize)
+        }
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    pub fn set_selected_index(&self, index: Option<usize>) {
+        self.element
+            .set_selected_index(index.map_or(-1, |i| i as i32))
+    }
+
+    // on the server the selected option is the one with the `selected` attribute, or the first
+    // option when none has it
+    #[cfg(feature = "ssr")]
+    fn options(&self) -> Vec<RawElement> {
+        fn collect(node: &RawElement, options: &mut Vec<RawElement>) {
+            for child in node.children() {
+                if child.tag_name() == "option" {
+                    options.push(child);
+                } else {
+                    collect(&child, options);
+                }
+            }
+        }
+        let mut options = Vec::new();
+        collect(&self.element, &mut options);
+        options
+    }
+
+    #[cfg(feature = "ssr")]
+    fn option_value(option: &RawElement) -> String {
+        option
+            .get_attribute("value")
+            .unwrap_or_else(|| option.text_content())
+    }
+
+    #[cfg(feature = "ssr")]
+    pub fn value(&self) -> String {
+        let options = self.options();
+        self.selected_index()
+            .map(|i| Self::option_value(&options[i]))
+            .unwrap_or_default()
+    }
+
+    #[cfg(feature = "ssr")]
+    pub fn set_value(&self, value: impl AsRef<str>) {
+        let index = self
+            .options()
+            .iter()
+            .position(|o| Self::option_value(o) == value.as_ref());
+        self.set_selected_index(index)
+    }
+
+    #[cfg(feature = "ssr")]
+    pub fn selected_index(&self) -> Option<usize> {
+        let options = self.options();
+        options
+            .iter()
+            .position(|o| o.get_attribute("selected").is_some())
+            .or((!options.is_empty()).then_some(0))
+    }
+
+    #[cfg(feature = "ssr")]
+    pub fn set_selected_index(&self, index: Option<usize>) {
+        for (i, option) in self.options().iter().enumerate() {
+            let _ = if Some(i) == index {
+                option.set_attribute("selected", "")
+            } else {
+                option.remove_attribute("selected")
+            };
+        }
     }
 }
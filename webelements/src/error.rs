@@ -0,0 +1,204 @@
+//! The error type of the crate. Errors thrown by javascript keep the details of the exception
+//! and any error can be given context about what was being done with [`Context::context`].
+
+use std::{fmt::Display, str::FromStr};
+
+use wasm_bindgen::{JsCast, JsValue};
+
+use crate::Mismatch;
+
+#[non_exhaustive]
+#[derive(Debug)]
+pub enum Error {
+    /// an exception thrown by javascript
+    JsError(JsError),
+    Cast(&'static str),
+    Window,
+    Document,
+    Body,
+    Value,
+    /// a value that could not be parsed to the type it was read as
+    Parse {
+        value: String,
+        /// the name of the type
+        ty: &'static str,
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+    Hydrate(Mismatch),
+    /// a node that should be in the dom is not there
+    NotFound,
+    /// an error with a description of what was being done when it happened
+    Context {
+        context: String,
+        source: Box<Error>,
+    },
+}
+
+impl Error {
+    pub fn as_jsvalue(&self) -> JsValue {
+        match self {
+            Error::JsError(JsError {
+                value: Some(value), ..
+            }) => value.clone(),
+            _ => JsValue::from_str(&self.to_string()),
+        }
+    }
+
+    /// an error with only a message, as if thrown by javascript
+    pub fn js_str(value: impl AsRef<str>) -> Error {
+        Error::JsError(JsError {
+            name: None,
+            message: value.as_ref().to_owned(),
+            stack: None,
+            value: None,
+        })
+    }
+
+    /// the error for `value` that could not be parsed as `T`
+    pub fn parse<T>(value: impl Into<String>, error: T::Err) -> Error
+    where
+        T: FromStr,
+        T::Err: std::error::Error + Send + Sync + 'static,
+    {
+        Error::Parse {
+            value: value.into(),
+            ty: std::any::type_name::<T>(),
+            source: Box::new(error),
+        }
+    }
+
+    /// wraps the error with a description of what was being done when it happened
+    pub fn context(self, context: impl Into<String>) -> Error {
+        Error::Context {
+            context: context.into(),
+            source: Box::new(self),
+        }
+    }
+
+    /// the error without the context it was given
+    pub fn root_cause(&self) -> &Error {
+        match self {
+            Error::Context { source, .. } => source.root_cause(),
+            error => error,
+        }
+    }
+}
+
+impl From<JsValue> for Error {
+    fn from(from: JsValue) -> Self {
+        Error::JsError(from.into())
+    }
+}
+
+impl From<Error> for JsValue {
+    fn from(e: Error) -> Self {
+        e.as_jsvalue()
+    }
+}
+
+/// the context is written before the error it wraps, which is also its `source`
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::JsError(e) => write!(f, "{}", e),
+            Error::Cast(t) => write!(f, "unable to cast value to type `{}`", t),
+            Error::Window => write!(f, "there is no global `window`"),
+            Error::Document => write!(f, "the window has no `document`"),
+            Error::Body => write!(f, "the document has no `body`"),
+            Error::Value => write!(f, "invalid value"),
+            Error::Parse { value, ty, source } => {
+                write!(f, "`{}` is not a valid `{}`: {}", value, ty, source)
+            }
+            Error::Hydrate(m) => write!(f, "{}", m),
+            Error::NotFound => write!(f, "a node that should be in the dom is not there"),
+            Error::Context { context, source } => write!(f, "{}: {}", context, source),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Parse { source, .. } => Some(source.as_ref()),
+            Error::Context { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// The details of an exception thrown by javascript. `name`, `message` and `stack` are read
+/// from `Error` objects, other thrown values are used as message.
+#[derive(Debug, Clone)]
+pub struct JsError {
+    /// the type of the error, like `TypeError` or `NotFoundError`
+    pub name: Option<String>,
+    pub message: String,
+    pub stack: Option<String>,
+    /// the value that was thrown, `None` for errors made in rust
+    pub value: Option<JsValue>,
+}
+
+impl From<JsValue> for JsError {
+    fn from(value: JsValue) -> Self {
+        if let Some(message) = value.as_string() {
+            return JsError {
+                name: None,
+                message,
+                stack: None,
+                value: Some(value),
+            };
+        }
+        // dom exceptions are not always `Error` objects, their properties are read directly
+        let property = |key: &str| {
+            value
+                .dyn_ref::<js_sys::Object>()
+                .and_then(|object| js_sys::Reflect::get(object, &JsValue::from_str(key)).ok())
+                .and_then(|v| v.as_string())
+        };
+        let message = property("message").unwrap_or_else(|| format!("{:?}", value));
+        JsError {
+            name: property("name"),
+            message,
+            stack: property("stack"),
+            value: Some(value),
+        }
+    }
+}
+
+/// `{:#}` also writes the stack
+impl Display for JsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.name {
+            Some(name) => write!(f, "{}: {}", name, self.message)?,
+            None => write!(f, "{}", self.message)?,
+        }
+        match &self.stack {
+            Some(stack) if f.alternate() => write!(f, "\n{}", stack),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Adds context to the error of a `Result`.
+///
+/// ```ignore
+/// element.set_attr("data-id", id).context("showing the item")?;
+/// ```
+pub trait Context<T> {
+    fn context(self, context: impl Into<String>) -> Result<T>;
+
+    /// like [`context`](Self::context), the context is only made when there is an error
+    fn with_context<C: Into<String>>(self, context: impl FnOnce() -> C) -> Result<T>;
+}
+
+impl<T, E: Into<Error>> Context<T> for std::result::Result<T, E> {
+    fn context(self, context: impl Into<String>) -> Result<T> {
+        self.map_err(|e| e.into().context(context))
+    }
+
+    fn with_context<C: Into<String>>(self, context: impl FnOnce() -> C) -> Result<T> {
+        self.map_err(|e| e.into().context(context()))
+    }
+}
@@ -0,0 +1,202 @@
+//! Typed events that can be listened to with [`Element::on`](crate::Element::on) and
+//! [`Document::on`](crate::Document::on).
+
+use wasm_bindgen::JsCast;
+#[cfg(not(feature = "ssr"))]
+use wasm_bindgen::{prelude::Closure, JsValue};
+
+#[cfg(not(feature = "ssr"))]
+use crate::Context;
+use crate::Result;
+
+/// An event that can be listened to, `NAME` is the name of the event in the dom and `Event`
+/// the type the callback receives.
+pub trait Event {
+    const NAME: &'static str;
+    type Event: JsCast + 'static;
+}
+
+macro_rules! events {
+    ($( $name:ident($event:literal) => $ty:ident ),* $(,)?) => {
+        $(
+            #[doc = concat!("The `", $event, "` event")]
+            #[derive(Debug, Clone, Copy)]
+            pub struct $name;
+            impl Event for $name {
+                const NAME: &'static str = $event;
+                type Event = web_sys::$ty;
+            }
+        )*
+    };
+}
+
+events! {
+    Click("click") => MouseEvent,
+    DblClick("dblclick") => MouseEvent,
+    ContextMenu("contextmenu") => MouseEvent,
+    MouseDown("mousedown") => MouseEvent,
+    MouseUp("mouseup") => MouseEvent,
+    MouseEnter("mouseenter") => MouseEvent,
+    MouseLeave("mouseleave") => MouseEvent,
+    MouseMove("mousemove") => MouseEvent,
+    MouseOver("mouseover") => MouseEvent,
+    MouseOut("mouseout") => MouseEvent,
+    Wheel("wheel") => WheelEvent,
+    KeyDown("keydown") => KeyboardEvent,
+    KeyUp("keyup") => KeyboardEvent,
+    Input("input") => InputEvent,
+    BeforeInput("beforeinput") => InputEvent,
+    Change("change") => Event,
+    Focus("focus") => FocusEvent,
+    Blur("blur") => FocusEvent,
+    FocusIn("focusin") => FocusEvent,
+    FocusOut("focusout") => FocusEvent,
+    PointerDown("pointerdown") => PointerEvent,
+    PointerUp("pointerup") => PointerEvent,
+    PointerMove("pointermove") => PointerEvent,
+    PointerEnter("pointerenter") => PointerEvent,
+    PointerLeave("pointerleave") => PointerEvent,
+    PointerOver("pointerover") => PointerEvent,
+    PointerOut("pointerout") => PointerEvent,
+    PointerCancel("pointercancel") => PointerEvent,
+    Drag("drag") => DragEvent,
+    DragStart("dragstart") => DragEvent,
+    DragEnd("dragend") => DragEvent,
+    DragEnter("dragenter") => DragEvent,
+    DragLeave("dragleave") => DragEvent,
+    DragOver("dragover") => DragEvent,
+    Drop("drop") => DragEvent,
+    TouchStart("touchstart") => TouchEvent,
+    TouchEnd("touchend") => TouchEvent,
+    TouchMove("touchmove") => TouchEvent,
+    TouchCancel("touchcancel") => TouchEvent,
+    Submit("submit") => SubmitEvent,
+    Reset("reset") => Event,
+    Scroll("scroll") => Event,
+    Load("load") => Event,
+}
+
+/// Options for adding an event listener.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ListenerOptions {
+    pub once: bool,
+    pub passive: bool,
+    pub capture: bool,
+}
+
+impl ListenerOptions {
+    /// the listener is removed after it is called once
+    pub fn once(mut self) -> Self {
+        self.once = true;
+        self
+    }
+
+    /// the listener will never call `prevent_default`
+    pub fn passive(mut self) -> Self {
+        self.passive = true;
+        self
+    }
+
+    /// the listener is called in the capture phase
+    pub fn capture(mut self) -> Self {
+        self.capture = true;
+        self
+    }
+}
+
+#[cfg(not(feature = "ssr"))]
+#[derive(Debug)]
+struct Listener {
+    target: web_sys::EventTarget,
+    event: String,
+    capture: bool,
+    closure: Closure<dyn FnMut(JsValue)>,
+}
+
+/// Handle to an event listener, the listener is removed and its callback dropped when the
+/// handle is dropped.
+#[derive(Debug)]
+#[must_use = "the listener is removed when the handle is dropped"]
+pub struct ListenerHandle {
+    #[cfg(not(feature = "ssr"))]
+    listener: Option<Listener>,
+}
+
+impl ListenerHandle {
+    /// keeps the listener for as long as the page lives
+    pub fn forget(self) {
+        #[cfg(not(feature = "ssr"))]
+        {
+            let mut handle = self;
+            if let Some(listener) = handle.listener.take() {
+                listener.closure.forget();
+            }
+        }
+    }
+}
+
+impl std::ops::Drop for ListenerHandle {
+    fn drop(&mut self) {
+        #[cfg(not(feature = "ssr"))]
+        if let Some(listener) = self.listener.take() {
+            let _ = listener
+                .target
+                .remove_event_listener_with_callback_and_bool(
+                    &listener.event,
+                    listener.closure.as_ref().unchecked_ref(),
+                    listener.capture,
+                );
+        }
+    }
+}
+
+/// adds `callback` as listener for `event` on `target`
+#[cfg(not(feature = "ssr"))]
+pub(crate) fn listen<Ev, T>(
+    target: &T,
+    event: &str,
+    options: ListenerOptions,
+    mut callback: impl FnMut(Ev) + 'static,
+) -> Result<ListenerHandle>
+where
+    Ev: JsCast + 'static,
+    T: AsRef<web_sys::EventTarget>,
+{
+    let target = target.as_ref();
+    let closure = Closure::wrap(
+        Box::new(move |e: JsValue| callback(e.unchecked_into())) as Box<dyn FnMut(JsValue)>
+    );
+    let js_options = web_sys::AddEventListenerOptions::new();
+    js_options.set_once(options.once);
+    js_options.set_passive(options.passive);
+    js_options.set_capture(options.capture);
+    target
+        .add_event_listener_with_callback_and_add_event_listener_options(
+            event,
+            closure.as_ref().unchecked_ref(),
+            &js_options,
+        )
+        .with_context(|| format!("adding a `{}` listener", event))?;
+    Ok(ListenerHandle {
+        listener: Some(Listener {
+            target: target.clone(),
+            event: event.to_owned(),
+            capture: options.capture,
+            closure,
+        }),
+    })
+}
+
+// there are no events on the server, the callback is dropped and never called
+#[cfg(feature = "ssr")]
+pub(crate) fn listen<Ev, T: ?Sized>(
+    _target: &T,
+    _event: &str,
+    _options: ListenerOptions,
+    _callback: impl FnMut(Ev) + 'static,
+) -> Result<ListenerHandle>
+where
+    Ev: JsCast + 'static,
+{
+    Ok(ListenerHandle {})
+}
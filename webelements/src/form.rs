@@ -0,0 +1,418 @@
+//! Forms that keep a struct in sync with the inputs of an element. The struct derives
+//! [`WeForm`](crate::WeForm), which maps its fields to the inputs with the same `we_field` name
+//! and runs their validators.
+
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    rc::{Rc, Weak},
+};
+
+use crate::{
+    elem, event, reactive::Subscription, Element, Error, ListenerHandle, Memo, Observable, Result,
+    Signal,
+};
+
+/// A struct that is edited with the inputs of a view, implemented with `#[derive(WeForm)]`.
+pub trait FormModel: Clone + 'static {
+    /// the element that has the inputs as fields
+    type View: Clone + 'static;
+
+    /// the names of the fields of the form
+    const FIELDS: &'static [&'static str];
+
+    /// the input of each field in the view
+    fn controls(view: &Self::View) -> Vec<(&'static str, &dyn FormControl)>;
+
+    /// sets `field` to the value of its input
+    fn read(&mut self, view: &Self::View, field: &str) -> Result<()>;
+
+    /// sets the inputs to the values of the fields
+    fn write(&self, view: &Self::View);
+
+    /// runs the validators of the fields and of the struct
+    fn validate(&self, errors: &mut FormErrors);
+}
+
+/// An element that holds the value of a form field.
+pub trait FormControl {
+    fn read_value(&self) -> String;
+
+    fn write_value(&self, value: &str);
+
+    /// whether a checkbox or radio input is checked, `None` for other controls
+    fn read_checked(&self) -> Option<bool> {
+        None
+    }
+
+    fn write_checked(&self, _checked: bool) {}
+
+    /// marks the control as invalid with `aria-invalid`
+    fn set_invalid(&self, invalid: bool);
+
+    /// calls `callback` every time the value is changed by the user
+    fn on_change(&self, callback: Box<dyn FnMut()>) -> Result<ListenerHandle>;
+}
+
+fn set_invalid<E: elem::ElemTy>(element: &Element<E>, invalid: bool) {
+    // `aria-invalid` is a valid attribute name, setting it can't fail
+    let _ = if invalid {
+        element.set_attr("aria-invalid", "true")
+    } else {
+        element.del_attr("aria-invalid")
+    };
+}
+
+impl FormControl for Element<elem::Input> {
+    fn read_value(&self) -> String {
+        self.value()
+    }
+
+    fn write_value(&self, value: &str) {
+        self.set_value(value)
+    }
+
+    fn read_checked(&self) -> Option<bool> {
+        match self.attr("type").as_deref() {
+            Some("checkbox") | Some("radio") => Some(self.checked()),
+            _ => None,
+        }
+    }
+
+    fn write_checked(&self, checked: bool) {
+        self.set_checked(checked)
+    }
+
+    fn set_invalid(&self, invalid: bool) {
+        set_invalid(self, invalid)
+    }
+
+    fn on_change(&self, mut callback: Box<dyn FnMut()>) -> Result<ListenerHandle> {
+        self.on::<event::Input>(move |_| callback())
+    }
+}
+
+impl FormControl for Element<elem::Textarea> {
+    fn read_value(&self) -> String {
+        self.value()
+    }
+
+    fn write_value(&self, value: &str) {
+        self.set_value(value)
+    }
+
+    fn set_invalid(&self, invalid: bool) {
+        set_invalid(self, invalid)
+    }
+
+    fn on_change(&self, mut callback: Box<dyn FnMut()>) -> Result<ListenerHandle> {
+        self.on::<event::Input>(move |_| callback())
+    }
+}
+
+impl FormControl for Element<elem::Select> {
+    fn read_value(&self) -> String {
+        self.value()
+    }
+
+    fn write_value(&self, value: &str) {
+        self.set_value(value)
+    }
+
+    fn set_invalid(&self, invalid: bool) {
+        set_invalid(self, invalid)
+    }
+
+    fn on_change(&self, mut callback: Box<dyn FnMut()>) -> Result<ListenerHandle> {
+        self.on::<event::Change>(move |_| callback())
+    }
+}
+
+/// A type a form field can have, read from and written to its control.
+pub trait FormValue: Sized {
+    fn read(control: &dyn FormControl) -> Result<Self>;
+
+    fn write(&self, control: &dyn FormControl);
+}
+
+impl FormValue for String {
+    fn read(control: &dyn FormControl) -> Result<Self> {
+        Ok(control.read_value())
+    }
+
+    fn write(&self, control: &dyn FormControl) {
+        control.write_value(self)
+    }
+}
+
+/// checkboxes are checked, other controls have the value `true` or `false`
+impl FormValue for bool {
+    fn read(control: &dyn FormControl) -> Result<Self> {
+        match control.read_checked() {
+            Some(checked) => Ok(checked),
+            None => parse(control),
+        }
+    }
+
+    fn write(&self, control: &dyn FormControl) {
+        match control.read_checked() {
+            Some(_) => control.write_checked(*self),
+            None => control.write_value(&self.to_string()),
+        }
+    }
+}
+
+/// an empty control is `None`
+impl<T: FormValue> FormValue for Option<T> {
+    fn read(control: &dyn FormControl) -> Result<Self> {
+        if control.read_checked().is_none() && control.read_value().trim().is_empty() {
+            Ok(None)
+        } else {
+            T::read(control).map(Some)
+        }
+    }
+
+    fn write(&self, control: &dyn FormControl) {
+        match self {
+            Some(value) => value.write(control),
+            None => control.write_value(""),
+        }
+    }
+}
+
+fn parse<T>(control: &dyn FormControl) -> Result<T>
+where
+    T: std::str::FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    let value = control.read_value();
+    value
+        .trim()
+        .parse()
+        .map_err(|e| Error::parse::<T>(value, e))
+}
+
+macro_rules! parsed_values {
+    ($( $ty:ty ),*) => {
+        $(
+            impl FormValue for $ty {
+                fn read(control: &dyn FormControl) -> Result<Self> {
+                    parse(control)
+                }
+
+                fn write(&self, control: &dyn FormControl) {
+                    control.write_value(&self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+parsed_values!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, char);
+
+/// The validation errors of a form, by field.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FormErrors {
+    errors: Vec<(&'static str, String)>,
+}
+
+impl FormErrors {
+    pub fn add(&mut self, field: &'static str, message: impl Into<String>) {
+        self.errors.push((field, message.into()));
+    }
+
+    /// the first error of `field`
+    pub fn get(&self, field: &str) -> Option<&str> {
+        self.errors
+            .iter()
+            .find(|(f, _)| *f == field)
+            .map(|(_, m)| m.as_str())
+    }
+
+    pub fn has(&self, field: &str) -> bool {
+        self.get(field).is_some()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// the fields and their errors in the order they were added
+    pub fn iter(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        self.errors.iter().map(|(f, m)| (*f, m.as_str()))
+    }
+}
+
+struct FormInner<M: FormModel> {
+    view: M::View,
+    value: Signal<M>,
+    /// the errors of the fields that were changed, or of all fields after a submit
+    errors: Signal<FormErrors>,
+    /// inputs that could not be read into their field, the field keeps its last value
+    invalid: RefCell<FormErrors>,
+    touched: RefCell<HashSet<&'static str>>,
+    listeners: RefCell<Vec<ListenerHandle>>,
+}
+
+/// A struct kept in sync with the inputs of a view. Changes to the inputs are read into the
+/// struct and validated, the errors of a field are only shown after it was changed or the
+/// form was submitted. Cloning a form gives a new handle to the same form.
+pub struct Form<M: FormModel> {
+    inner: Rc<FormInner<M>>,
+}
+
+impl<M: FormModel> Clone for Form<M> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<M: FormModel + std::fmt::Debug> std::fmt::Debug for Form<M> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Form")
+            .field("value", &self.inner.value)
+            .field("errors", &self.inner.errors)
+            .finish()
+    }
+}
+
+impl<M: FormModel> Form<M> {
+    /// sets the inputs of `view` to `value` and listens to their changes
+    pub fn new(view: &M::View, value: M) -> Result<Form<M>> {
+        value.write(view);
+        let form = Form {
+            inner: Rc::new(FormInner {
+                view: view.clone(),
+                value: Signal::new(value),
+                errors: Signal::default(),
+                invalid: RefCell::default(),
+                touched: RefCell::default(),
+                listeners: RefCell::default(),
+            }),
+        };
+        for (field, control) in M::controls(view) {
+            // the listeners are owned by the form, they can't keep it alive
+            let inner: Weak<FormInner<M>> = Rc::downgrade(&form.inner);
+            let listener = control.on_change(Box::new(move || {
+                if let Some(inner) = inner.upgrade() {
+                    Form { inner }.update(field);
+                }
+            }))?;
+            form.inner.listeners.borrow_mut().push(listener);
+        }
+        Ok(form)
+    }
+
+    pub fn view(&self) -> &M::View {
+        &self.inner.view
+    }
+
+    /// sets the value and the inputs, the errors are updated for the fields that were changed
+    pub fn set(&self, value: M) {
+        value.write(&self.inner.view);
+        self.inner.invalid.replace(FormErrors::default());
+        self.inner.value.set(value);
+        self.validate();
+    }
+
+    /// reads `field` from its input and validates the form, this is done every time the input
+    /// is changed by the user
+    pub fn update(&self, field: &'static str) {
+        let mut value = self.get();
+        let result = value.read(&self.inner.view, field);
+        self.inner.touched.borrow_mut().insert(field);
+        {
+            let mut invalid = self.inner.invalid.borrow_mut();
+            invalid.errors.retain(|(f, _)| *f != field);
+            if let Err(e) = result {
+                invalid.add(field, error_message(e));
+            }
+        }
+        self.inner.value.set(value);
+        self.validate();
+    }
+
+    /// all errors of the form, including the fields that were not changed
+    pub fn check(&self) -> FormErrors {
+        let mut errors = self.inner.invalid.borrow().clone();
+        self.inner.value.with(|value| value.validate(&mut errors));
+        errors
+    }
+
+    fn validate(&self) {
+        let touched = self.inner.touched.borrow();
+        let mut errors = self.check();
+        errors.errors.retain(|(f, _)| touched.contains(f));
+        for (field, control) in M::controls(&self.inner.view) {
+            control.set_invalid(errors.has(field));
+        }
+        self.inner.errors.set(errors);
+    }
+
+    /// the errors that are shown
+    pub fn errors(&self) -> Signal<FormErrors> {
+        self.inner.errors.clone()
+    }
+
+    /// the first error of `field` that is shown, empty when there is none. bind it to the
+    /// text of the element that displays it.
+    pub fn field_error(&self, field: &'static str) -> Memo<String> {
+        self.inner
+            .errors
+            .map(move |errors| errors.get(field).unwrap_or_default().to_owned())
+    }
+
+    /// reads all inputs and shows the errors of all fields, the value is returned when it is
+    /// valid
+    pub fn submit(&self) -> std::result::Result<M, FormErrors> {
+        self.inner
+            .touched
+            .borrow_mut()
+            .extend(M::FIELDS.iter().copied());
+        for field in M::FIELDS {
+            self.update(field);
+        }
+        let errors = self.check();
+        if errors.is_empty() {
+            Ok(self.get())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// calls `callback` with the value when `form` is submitted and the value is valid. the
+    /// browser does not submit the form itself.
+    pub fn on_submit(
+        &self,
+        form: &Element<elem::Form>,
+        mut callback: impl FnMut(M) + 'static,
+    ) -> Result<ListenerHandle> {
+        let this = self.clone();
+        form.on::<event::Submit>(move |e| {
+            e.prevent_default();
+            if let Ok(value) = this.submit() {
+                callback(value);
+            }
+        })
+    }
+}
+
+impl<M: FormModel> Observable<M> for Form<M> {
+    fn with<R>(&self, f: impl FnOnce(&M) -> R) -> R {
+        self.inner.value.with(f)
+    }
+
+    fn subscribe(&self, f: impl FnMut(&M) + 'static) -> Subscription {
+        self.inner.value.subscribe(f)
+    }
+}
+
+/// parse errors are shown without the value and type, the user can see the value
+fn error_message(error: Error) -> String {
+    match error {
+        Error::Parse { source, .. } => source.to_string(),
+        error => error.to_string(),
+    }
+}
@@ -0,0 +1,172 @@
+//! Matching elements that are already in the dom, for example rendered on the server, to the
+//! template of a [`WebElementBuilder`](crate::WebElementBuilder).
+
+use std::fmt::Display;
+
+use crate::{
+    element::{as_node, elem::ElemTy, RawElement, RawNode, XHTML_NAMESPACE},
+    Element, Error, Result, TextNode,
+};
+
+/// The dom differs from the template that is hydrated.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mismatch {
+    /// tags and positions of the elements leading from the root to the mismatched element
+    pub path: Vec<String>,
+    /// the tag the template expects, `None` when the dom has more elements than the template
+    pub expected: Option<String>,
+    /// the tag found in the dom, `None` when the dom has fewer elements than the template
+    pub found: Option<String>,
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "hydration mismatch at `{}`: ", self.path.join(" > "))?;
+        match (&self.expected, &self.found) {
+            (Some(expected), Some(found)) => {
+                write!(f, "expected `<{}>`, found `<{}>`", expected, found)
+            }
+            (Some(expected), None) => write!(f, "expected `<{}>`, found nothing", expected),
+            (None, Some(found)) => write!(f, "unexpected `<{}>`", found),
+            (None, None) => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Walks the element children of a node in template order.
+#[derive(Debug)]
+pub struct Hydrator {
+    /// the element whose children are walked, `None` for the root
+    parent: Option<RawElement>,
+    path: Vec<String>,
+    nodes: Vec<RawElement>,
+    index: usize,
+}
+
+impl Hydrator {
+    /// a hydrator that will match `root` to the root element of a template
+    pub fn new(root: &RawElement) -> Hydrator {
+        Hydrator {
+            parent: None,
+            path: Vec::new(),
+            nodes: vec![root.clone()],
+            index: 0,
+        }
+    }
+
+    fn mismatch(&self, expected: Option<&str>, found: Option<&RawElement>) -> Error {
+        let found = found.map(tag_name);
+        let mut path = self.path.clone();
+        let tag = expected.or(found.as_deref()).unwrap_or_default();
+        path.push(format!("{}[{}]", tag, self.index));
+        Error::Hydrate(Mismatch {
+            path,
+            expected: expected.map(ToOwned::to_owned),
+            found,
+        })
+    }
+
+    /// takes the next element, checking that it has the tag and namespace of `E`
+    pub fn element<E: ElemTy>(&mut self) -> Result<Element<E>> {
+        let node = match self.nodes.get(self.index) {
+            Some(node) => node.clone(),
+            None => return Err(self.mismatch(Some(E::TAG), None)),
+        };
+        if !matches::<E>(&node) {
+            return Err(self.mismatch(Some(E::TAG), Some(&node)));
+        }
+        self.index += 1;
+        Element::from_raw(node)
+    }
+
+    /// takes the text node after the last element, or at the start of the parent. `before` and
+    /// `after` are the lengths of the static text around it in the template, that text was
+    /// merged with it in the html and is split off. an empty text node is inserted if there is
+    /// no text.
+    pub fn text(&mut self, before: u32, after: u32) -> Result<TextNode> {
+        let parent = self.parent.as_ref().ok_or(Error::NotFound)?;
+        let node = match self.index.checked_sub(1).and_then(|i| self.nodes.get(i)) {
+            Some(previous) => previous.next_sibling(),
+            None => parent.first_child(),
+        };
+        let text = match node.and_then(TextNode::from_node) {
+            Some(text) if before > 0 => text.split(before)?,
+            Some(text) => text,
+            None if before > 0 || after > 0 => {
+                return Err(self.mismatch(Some("#text"), self.nodes.get(self.index)))
+            }
+            None => {
+                let text = TextNode::new("")?;
+                self.insert(text.as_node())?;
+                return Ok(text);
+            }
+        };
+        match text.len().checked_sub(after) {
+            Some(end) if after > 0 => {
+                text.split(end)?;
+            }
+            Some(_) => {}
+            None => return Err(self.mismatch(Some("#text"), self.nodes.get(self.index))),
+        }
+        Ok(text)
+    }
+
+    /// inserts `node` in the dom before the next element, returns the parent it was added to
+    pub(crate) fn insert(&self, node: &RawNode) -> Result<RawElement> {
+        let parent = self.parent.as_ref().ok_or(Error::NotFound)?;
+        match self.nodes.get(self.index) {
+            Some(next) => parent.insert_before(node, Some(as_node(next)))?,
+            None => parent.append_child(node)?,
+        };
+        Ok(parent.clone())
+    }
+
+    /// a hydrator over the children of `parent`
+    pub fn children<E: ElemTy>(&self, parent: &Element<E>) -> Hydrator {
+        let mut path = self.path.clone();
+        path.push(format!("{}[{}]", E::TAG, self.index.saturating_sub(1)));
+        Hydrator {
+            parent: Some(parent.dom_element().clone()),
+            path,
+            nodes: element_children(parent.dom_element()),
+            index: 0,
+        }
+    }
+
+    /// checks that all elements were matched to the template
+    pub fn finish(self) -> Result<()> {
+        match self.nodes.get(self.index) {
+            Some(node) => Err(self.mismatch(None, Some(node))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// whether `node` has the tag and namespace of `E`
+fn matches<E: ElemTy>(node: &RawElement) -> bool {
+    let namespace = node.namespace_uri().filter(|ns| ns != XHTML_NAMESPACE);
+    tag_name(node).eq_ignore_ascii_case(E::TAG) && namespace.as_deref() == E::NAMESPACE
+}
+
+#[cfg(not(feature = "ssr"))]
+fn tag_name(node: &RawElement) -> String {
+    node.tag_name().to_lowercase()
+}
+
+#[cfg(feature = "ssr")]
+fn tag_name(node: &RawElement) -> String {
+    node.tag_name()
+}
+
+#[cfg(not(feature = "ssr"))]
+fn element_children(node: &RawElement) -> Vec<RawElement> {
+    let children = node.children();
+    (0..children.length())
+        .filter_map(|i| children.item(i))
+        .collect()
+}
+
+#[cfg(feature = "ssr")]
+fn element_children(node: &RawElement) -> Vec<RawElement> {
+    node.children()
+}
@@ -1,70 +1,34 @@
+pub mod conditional;
 pub mod element;
-
-use std::{fmt::Display, ops::Deref};
+pub mod error;
+pub mod event;
+pub mod form;
+pub mod hydrate;
+pub mod lifecycle;
+pub mod list;
+pub mod reactive;
+pub mod sanitize;
+#[cfg(feature = "ssr")]
+pub mod ssr;
+pub mod text;
+
+use std::ops::Deref;
 
 use wasm_bindgen::{prelude::*, JsCast, JsValue};
 
-pub use element::{elem, Element, WebElement, WebElementBuilder};
-pub use we_derive::{we_builder, WebElement};
+pub use conditional::{Condition, Conditional};
+pub use element::{elem, AnyElement, Element, WebElement, WebElementBuilder};
+pub use error::{Context, Error, JsError, Result};
+use event::Event;
+pub use event::{ListenerHandle, ListenerOptions};
+pub use form::{Form, FormErrors};
+pub use hydrate::{Hydrator, Mismatch};
+pub use list::{ElementList, ListSource};
+pub use reactive::{Memo, Observable, Signal, Subscription};
+pub use text::TextNode;
+pub use we_derive::{we_builder, WeForm, WebElement};
 use web_sys::{KeyboardEvent, MessageEvent, MouseEvent};
 
-#[non_exhaustive]
-#[derive(Debug)]
-pub enum Error {
-    JsError(JsValue),
-    Cast(&'static str),
-    Window,
-    Document,
-    Body,
-    Value,
-}
-
-impl From<JsValue> for Error {
-    fn from(from: JsValue) -> Self {
-        Error::JsError(from)
-    }
-}
-
-impl From<Error> for JsValue {
-    fn from(e: Error) -> Self {
-        e.as_jsvalue()
-    }
-}
-
-impl Display for Error {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Error::JsError(s) => {
-                if let Some(s) = s.as_string() {
-                    write!(f, "{}", s)
-                } else {
-                    Err(std::fmt::Error)
-                }
-            }
-            Error::Cast(t) => writeln!(f, "unable to cast value to type `{}`", t),
-            n => writeln!(f, "{:?}", n),
-        }
-    }
-}
-
-impl Error {
-    pub fn as_jsvalue(&self) -> JsValue {
-        if let Self::JsError(jsvalue) = self {
-            jsvalue.clone()
-        } else {
-            JsValue::from_str(&self.to_string())
-        }
-    }
-
-    pub fn js_str(value: impl AsRef<str>) -> Error {
-        Error::JsError(JsValue::from_str(value.as_ref()))
-    }
-}
-
-impl std::error::Error for Error {}
-
-pub type Result<T> = std::result::Result<T, Error>;
-
 pub struct Window {
     window: web_sys::Window,
 }
@@ -81,7 +45,7 @@ impl Window {
     pub fn on_animation(&self, callback: impl FnMut() + 'static) -> Result<()> {
         let closure = Closure::wrap(Box::new(callback) as Box<dyn FnMut()>);
         self.request_animation_frame(closure.as_ref().unchecked_ref())
-            .map_err(Error::JsError)?;
+            .context("requesting an animation frame")?;
         closure.forget();
         Ok(())
     }
@@ -93,64 +57,119 @@ pub fn window() -> Result<Window> {
     })
 }
 
+#[cfg(not(feature = "ssr"))]
+type RawDocument = web_sys::Document;
+#[cfg(feature = "ssr")]
+type RawDocument = ssr::Node;
+
 pub struct Document {
-    document: web_sys::Document,
+    document: RawDocument,
 }
 
 impl Document {
-    pub fn on_key(&self, mut callback: impl FnMut(KeyboardEvent) + 'static) -> Result<()> {
-        let closure =
-            Closure::wrap(Box::new(move |e| callback(e)) as Box<dyn FnMut(KeyboardEvent)>);
-        self.document
-            .add_event_listener_with_callback("keydown", closure.as_ref().unchecked_ref())
-            .map_err(Error::JsError)?;
-        closure.forget();
-        Ok(())
+    /// calls `callback` every time `Ev` is fired on the document until the returned handle is
+    /// dropped.
+    pub fn on<Ev: Event>(
+        &self,
+        callback: impl FnMut(Ev::Event) + 'static,
+    ) -> Result<ListenerHandle> {
+        self.on_with::<Ev>(ListenerOptions::default(), callback)
     }
 
+    /// like [`on`](Self::on) with options for the listener
+    pub fn on_with<Ev: Event>(
+        &self,
+        options: ListenerOptions,
+        callback: impl FnMut(Ev::Event) + 'static,
+    ) -> Result<ListenerHandle> {
+        event::listen(&self.document, Ev::NAME, options, callback)
+    }
 
-    pub fn on_mouseup(&self, mut callback: impl FnMut(MouseEvent) + 'static) -> Result<()> {
-        let closure =
-            Closure::wrap(Box::new(move |e| callback(e)) as Box<dyn FnMut(MouseEvent)>);
-        self.document
-            .add_event_listener_with_callback("mouseup", closure.as_ref().unchecked_ref())
-            .map_err(Error::JsError)?;
-        closure.forget();
-        Ok(())
+    pub fn on_key(&self, callback: impl FnMut(KeyboardEvent) + 'static) -> Result<()> {
+        self.on::<event::KeyDown>(callback)
+            .map(ListenerHandle::forget)
     }
 
+    pub fn on_mouseup(&self, callback: impl FnMut(MouseEvent) + 'static) -> Result<()> {
+        self.on::<event::MouseUp>(callback)
+            .map(ListenerHandle::forget)
+    }
 
+    pub fn on_click(&self, callback: impl FnMut(MouseEvent) + 'static) -> Result<()> {
+        self.on::<event::Click>(callback)
+            .map(ListenerHandle::forget)
+    }
 
-    pub fn on_click(&self, mut callback: impl FnMut(MouseEvent) + 'static) -> Result<()> {
-        let closure =
-            Closure::wrap(Box::new(move |e| callback(e)) as Box<dyn FnMut(MouseEvent)>);
-        self.document
-            .add_event_listener_with_callback("click", closure.as_ref().unchecked_ref())
-            .map_err(Error::JsError)?;
-        closure.forget();
-        Ok(())
+    /// the first element in the document that matches `selector`, `Error::Cast` when it is not
+    /// a `E`. with the `ssr` feature only simple selectors are supported, see
+    /// `ssr::Node::query_selector`.
+    pub fn query<E: elem::ElemTy>(&self, selector: &str) -> Result<Option<Element<E>>> {
+        element::query_one(self.document.query_selector(selector), selector)
+    }
+
+    /// all elements in the document that match `selector`, `Error::Cast` when one of them is
+    /// not a `E`
+    pub fn query_all<E: elem::ElemTy>(&self, selector: &str) -> Result<Vec<Element<E>>> {
+        element::query_all(self.document.query_selector_all(selector), selector)
     }
 
+    /// the element with the id `id`, `Error::Cast` when it is not a `E`
+    pub fn by_id<E: elem::ElemTy>(&self, id: &str) -> Result<Option<Element<E>>> {
+        self.query(&element::id_selector(id))
+    }
+
+    #[cfg(not(feature = "ssr"))]
     pub fn body(&self) -> Result<Element<crate::elem::Base>> {
         let element = self.document.body().ok_or(Error::Body)?;
         Ok(Element::from_element(element))
     }
+
+    #[cfg(feature = "ssr")]
+    pub fn body(&self) -> Result<Element<crate::elem::Base>> {
+        let html = self.document.children().into_iter().next();
+        let body = html
+            .and_then(|html| html.children().into_iter().find(|c| c.tag_name() == "body"))
+            .ok_or(Error::Body)?;
+        Element::from_raw(body)
+    }
 }
 
 impl Deref for Document {
-    type Target = web_sys::Document;
+    type Target = RawDocument;
 
     fn deref(&self) -> &Self::Target {
         &self.document
     }
 }
 
+#[cfg(not(feature = "ssr"))]
 pub fn document() -> Result<Document> {
     Ok(Document {
         document: window()?.document().ok_or(Error::Document)?,
     })
 }
 
+// on the server every thread has its own document, elements added to its body are mounted
+#[cfg(feature = "ssr")]
+pub fn document() -> Result<Document> {
+    thread_local! {
+        static DOCUMENT: ssr::Node = ssr::Node::document();
+    }
+    Ok(Document {
+        document: DOCUMENT.with(Clone::clone),
+    })
+}
+
+/// builds the element `T` and serializes it to html. with the `ssr` feature enabled this
+/// does not need a browser.
+pub fn render_to_string<T>() -> Result<String>
+where
+    T: WebElementBuilder + AsRef<Element<<T as WebElementBuilder>::Elem>>,
+    T::Props: Default,
+{
+    Ok(T::build()?.as_ref().to_html())
+}
+
 pub trait Loggable {
     fn log(self);
 }
@@ -165,8 +184,8 @@ impl<T> Loggable for Result<T> {
 
 pub mod internal {
     pub use js_sys::Array;
-    pub use web_sys::console::log;
     pub use wasm_bindgen::JsValue;
+    pub use web_sys::console::log;
 }
 
 #[macro_export]
@@ -194,10 +213,12 @@ impl Worker {
         let ctor = ctor
             .as_ref()
             .dyn_ref::<js_sys::Function>()
-            .ok_or(Error::Value)?;
+            .ok_or(Error::Cast("js_sys::Function"))?;
         let worker = ctor
-            .call0(&JsValue::null())?
-            .dyn_into::<web_sys::Worker>()?;
+            .call0(&JsValue::null())
+            .context("calling the worker constructor")?
+            .dyn_into::<web_sys::Worker>()
+            .map_err(|_| Error::Cast("web_sys::Worker"))?;
         Ok(Self { worker })
     }
 
@@ -229,7 +250,11 @@ pub struct Scope {
 impl Scope {
     pub fn new(scope: impl AsRef<JsValue>) -> Result<Self> {
         Ok(Self {
-            scope: scope.as_ref().clone().dyn_into()?,
+            scope: scope
+                .as_ref()
+                .clone()
+                .dyn_into()
+                .map_err(|_| Error::Cast("web_sys::DedicatedWorkerGlobalScope"))?,
         })
     }
     pub fn set_onmessage(&self, mut callback: impl FnMut(JsValue) + 'static) -> Result<()> {
@@ -0,0 +1,192 @@
+//! Calls the lifecycle hooks of [`WebElement`]s. Components are registered when they are built
+//! or hydrated. They are mounted when their root is added to the document and unmounted when it
+//! is removed. Only the components in the nodes that were added or removed are checked: the
+//! nodes added and removed with the methods of this crate right away, the nodes of other
+//! changes to the dom are found with a `MutationObserver` in the browser.
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
+
+#[cfg(not(feature = "ssr"))]
+use wasm_bindgen::{prelude::Closure, JsCast};
+
+use crate::{
+    element::{as_node, RawElement, RawNode},
+    Element, Result, WebElement,
+};
+
+/// the hooks of a component
+trait Hooks {
+    fn mounted(&self);
+    fn before_unmount(&self);
+    fn unmounted(&self);
+    fn updated(&self);
+    fn dropped(&self);
+}
+
+impl<T: WebElement> Hooks for T {
+    fn mounted(&self) {
+        WebElement::mounted(self)
+    }
+
+    fn before_unmount(&self) {
+        WebElement::before_unmount(self)
+    }
+
+    fn unmounted(&self) {
+        WebElement::unmounted(self)
+    }
+
+    fn updated(&self) {
+        WebElement::updated(self)
+    }
+
+    fn dropped(&self) {
+        WebElement::dropped(self)
+    }
+}
+
+struct Registration {
+    id: usize,
+    node: RawElement,
+    mounted: Cell<bool>,
+    /// a handle to the component that does not keep its root alive
+    hooks: Box<dyn Hooks>,
+}
+
+thread_local! {
+    static COMPONENTS: RefCell<Vec<Rc<Registration>>> = const { RefCell::new(Vec::new()) };
+    static NEXT_ID: Cell<usize> = const { Cell::new(0) };
+}
+
+/// registers the hooks of `component` until its root is dropped, called when a component is
+/// built or hydrated. the hooks are called on its [`unbound`](crate::WebElementBuilder::unbound)
+/// handle.
+pub fn register<T>(component: &T) -> Result<()>
+where
+    T: WebElement + AsRef<Element<T::Elem>> + 'static,
+{
+    let root = component.as_ref();
+    let id = NEXT_ID.with(|next| {
+        let id = next.get();
+        next.set(id + 1);
+        id
+    });
+    root.add_component(id);
+    let registration = Registration {
+        id,
+        node: root.dom_element().clone(),
+        mounted: Cell::new(false),
+        hooks: Box::new(component.unbound()),
+    };
+    COMPONENTS.with(|c| c.borrow_mut().push(Rc::new(registration)));
+    observe()?;
+    // a hydrated component is already in the document
+    check(as_node(root.dom_element()));
+    Ok(())
+}
+
+/// removes the component `id` after its root was dropped
+pub(crate) fn unregister(id: usize) {
+    // the components are gone when the thread is shutting down
+    let registration = COMPONENTS
+        .try_with(|c| {
+            let mut components = c.borrow_mut();
+            let index = components.iter().position(|r| r.id == id)?;
+            Some(components.remove(index))
+        })
+        .ok()
+        .flatten();
+    if let Some(registration) = registration {
+        registration.hooks.dropped();
+    }
+}
+
+/// the components `f` returns true for, the list is not borrowed while their hooks run so
+/// hooks can build and remove components
+fn components(f: impl Fn(&Registration) -> bool) -> Vec<Rc<Registration>> {
+    COMPONENTS.with(|c| c.borrow().iter().filter(|r| f(r)).cloned().collect())
+}
+
+/// calls `mounted` and `unmounted` for the components in `node`, after it was added to or
+/// removed from the document
+pub(crate) fn check(node: &RawNode) {
+    for registration in components(|r| node.contains(Some(as_node(&r.node)))) {
+        let connected = registration.node.is_connected();
+        if connected != registration.mounted.replace(connected) {
+            if connected {
+                registration.hooks.mounted();
+            } else {
+                registration.hooks.unmounted();
+            }
+        }
+    }
+}
+
+/// calls `before_unmount` for the mounted components in `node`, before it is removed
+pub(crate) fn before_remove(node: &RawNode) {
+    for registration in components(|r| r.mounted.get() && node.contains(Some(as_node(&r.node)))) {
+        registration.hooks.before_unmount();
+    }
+}
+
+/// calls `updated` for the closest component that contains `node`
+pub(crate) fn updated(node: &RawNode) {
+    let closest = COMPONENTS.with(|c| {
+        let components = c.borrow();
+        let mut node = Some(node.clone()).filter(|_| !components.is_empty());
+        while let Some(n) = node {
+            if let Some(registration) = components.iter().find(|r| *as_node(&r.node) == n) {
+                return Some(registration.clone());
+            }
+            node = n.parent_node();
+        }
+        None
+    });
+    if let Some(registration) = closest {
+        registration.hooks.updated();
+    }
+}
+
+/// watches the document for nodes that are added or removed without this crate
+#[cfg(not(feature = "ssr"))]
+fn observe() -> Result<()> {
+    thread_local! {
+        static OBSERVER: RefCell<Option<web_sys::MutationObserver>> = const { RefCell::new(None) };
+    }
+    OBSERVER.with(|observer| {
+        if observer.borrow().is_some() {
+            return Ok(());
+        }
+        let callback = Closure::wrap(Box::new(|records: js_sys::Array| {
+            for record in records.iter() {
+                let record: web_sys::MutationRecord = record.unchecked_into();
+                for nodes in [record.added_nodes(), record.removed_nodes()] {
+                    for i in 0..nodes.length() {
+                        if let Some(node) = nodes.get(i) {
+                            check(&node);
+                        }
+                    }
+                }
+            }
+        }) as Box<dyn FnMut(js_sys::Array)>);
+        let mutation_observer = web_sys::MutationObserver::new(callback.as_ref().unchecked_ref())?;
+        // the observer lives as long as the page
+        callback.forget();
+        let options = web_sys::MutationObserverInit::new();
+        options.set_child_list(true);
+        options.set_subtree(true);
+        let document = crate::document()?;
+        mutation_observer.observe_with_options(&document, &options)?;
+        *observer.borrow_mut() = Some(mutation_observer);
+        Ok(())
+    })
+}
+
+// the server dom only changes through this crate
+#[cfg(feature = "ssr")]
+fn observe() -> Result<()> {
+    Ok(())
+}
@@ -0,0 +1,397 @@
+//! Lists of elements rendered from a collection of items, declared in templates with `we_for`.
+
+use std::{cell::RefCell, collections::HashMap, hash::Hash, rc::Rc};
+
+use crate::{
+    element::{anchor, as_node, elem::ElemTy, RawElement, RawNode},
+    hydrate::Hydrator,
+    lifecycle,
+    reactive::{Memo, Observable, Signal, Subscription},
+    Element, Result,
+};
+
+/// A collection the items of an [`ElementList`] can be rendered from. Lists rendered from a
+/// signal or memo are updated when it changes.
+pub trait ListSource<T> {
+    fn with_items<R>(&self, f: impl FnOnce(&[T]) -> R) -> R;
+
+    /// calls `f` with the new items every time they change, `None` if they can't change
+    fn subscribe_items(&self, f: impl FnMut(&[T]) + 'static) -> Option<Subscription>;
+}
+
+impl<T> ListSource<T> for [T] {
+    fn with_items<R>(&self, f: impl FnOnce(&[T]) -> R) -> R {
+        f(self)
+    }
+
+    fn subscribe_items(&self, _f: impl FnMut(&[T]) + 'static) -> Option<Subscription> {
+        None
+    }
+}
+
+impl<T, const N: usize> ListSource<T> for [T; N] {
+    fn with_items<R>(&self, f: impl FnOnce(&[T]) -> R) -> R {
+        f(self)
+    }
+
+    fn subscribe_items(&self, _f: impl FnMut(&[T]) + 'static) -> Option<Subscription> {
+        None
+    }
+}
+
+impl<T> ListSource<T> for Vec<T> {
+    fn with_items<R>(&self, f: impl FnOnce(&[T]) -> R) -> R {
+        f(self)
+    }
+
+    fn subscribe_items(&self, _f: impl FnMut(&[T]) + 'static) -> Option<Subscription> {
+        None
+    }
+}
+
+impl<T: 'static> ListSource<T> for Signal<Vec<T>> {
+    fn with_items<R>(&self, f: impl FnOnce(&[T]) -> R) -> R {
+        self.with(|items| f(items))
+    }
+
+    fn subscribe_items(&self, mut f: impl FnMut(&[T]) + 'static) -> Option<Subscription> {
+        Some(self.subscribe(move |items| f(items)))
+    }
+}
+
+impl<T: 'static> ListSource<T> for Memo<Vec<T>> {
+    fn with_items<R>(&self, f: impl FnOnce(&[T]) -> R) -> R {
+        self.with(|items| f(items))
+    }
+
+    fn subscribe_items(&self, mut f: impl FnMut(&[T]) + 'static) -> Option<Subscription> {
+        Some(self.subscribe(move |items| f(items)))
+    }
+}
+
+type BuildFn<T, C> = Box<dyn Fn(&T) -> Result<C>>;
+
+struct ListInner<T, C, K> {
+    parent: RawElement,
+    anchor: RawNode,
+    key: Box<dyn Fn(&T) -> K>,
+    build: BuildFn<T, C>,
+    node: fn(&C) -> &RawElement,
+    children: Vec<(K, C)>,
+}
+
+/// One child element for every item of a collection, identified by a key. When the items are
+/// changed with [`set_items`](Self::set_items) the children of items with a key that was
+/// already in the list are kept, only new items are built and only children that are out of
+/// place are moved. Cloning a list gives a new handle to the same list.
+pub struct ElementList<T, C, K> {
+    inner: Rc<RefCell<ListInner<T, C, K>>>,
+}
+
+impl<T, C, K> Clone for ElementList<T, C, K> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T, C, K> std::fmt::Debug for ElementList<T, C, K> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ElementList")
+            .field("len", &self.inner.borrow().children.len())
+            .finish()
+    }
+}
+
+impl<T, C, K> ElementList<T, C, K>
+where
+    T: 'static,
+    C: 'static,
+    K: Eq + Hash + 'static,
+{
+    /// an empty list whose children are added at the end of `parent`. `key` identifies the
+    /// child of an item and `build` creates it.
+    pub fn new<P: ElemTy, E: ElemTy>(
+        parent: &Element<P>,
+        key: impl Fn(&T) -> K + 'static,
+        build: impl Fn(&T) -> Result<C> + 'static,
+    ) -> Result<Self>
+    where
+        C: AsRef<Element<E>>,
+    {
+        let anchor = anchor()?;
+        let parent = parent.dom_element().clone();
+        parent.append_child(&anchor)?;
+        Ok(Self::with_parts(
+            parent,
+            anchor,
+            Box::new(key),
+            Box::new(build),
+            Vec::new(),
+        ))
+    }
+
+    /// a list at the end of `parent` with the children for the items in `source`, if the
+    /// source is a signal the list is updated for as long as `parent` lives.
+    pub fn from_source<P: ElemTy, E: ElemTy, S: ListSource<T> + ?Sized>(
+        parent: &Element<P>,
+        source: &S,
+        key: impl Fn(&T) -> K + 'static,
+        build: impl Fn(&T) -> Result<C> + 'static,
+    ) -> Result<Self>
+    where
+        C: AsRef<Element<E>>,
+    {
+        let list = Self::new(parent, key, build)?;
+        source.with_items(|items| list.set_items(items))?;
+        list.follow(parent, source);
+        Ok(list)
+    }
+
+    /// a list for children that are already in the dom. they were rendered from the items in
+    /// `source`, the child of each item is taken from the dom with `hydrate`. after that the
+    /// list is updated like with [`from_source`](Self::from_source).
+    pub fn hydrate<P: ElemTy, E: ElemTy, S: ListSource<T> + ?Sized>(
+        parent: &Element<P>,
+        cursor: &mut Hydrator,
+        source: &S,
+        key: impl Fn(&T) -> K + 'static,
+        build: impl Fn(&T) -> Result<C> + 'static,
+        mut hydrate: impl FnMut(&mut Hydrator, &T) -> Result<C>,
+    ) -> Result<Self>
+    where
+        C: AsRef<Element<E>>,
+    {
+        let children = source.with_items(|items| {
+            items
+                .iter()
+                .map(|item| Ok((key(item), hydrate(cursor, item)?)))
+                .collect::<Result<Vec<_>>>()
+        })?;
+        let anchor = anchor()?;
+        let parent_node = cursor.insert(&anchor)?;
+        let list = Self::with_parts(
+            parent_node,
+            anchor,
+            Box::new(key),
+            Box::new(build),
+            children,
+        );
+        list.follow(parent, source);
+        Ok(list)
+    }
+
+    /// updates the list when the items in `source` change, for as long as `parent` lives
+    fn follow<P: ElemTy, S: ListSource<T> + ?Sized>(&self, parent: &Element<P>, source: &S) {
+        let updated = self.clone();
+        // errors can't be returned from a subscriber, the list is left as it was
+        if let Some(subscription) = source.subscribe_items(move |items| {
+            let _ = updated.set_items(items);
+        }) {
+            parent.keep(subscription);
+        }
+    }
+
+    fn with_parts<E: ElemTy>(
+        parent: RawElement,
+        anchor: RawNode,
+        key: Box<dyn Fn(&T) -> K>,
+        build: BuildFn<T, C>,
+        children: Vec<(K, C)>,
+    ) -> Self
+    where
+        C: AsRef<Element<E>>,
+    {
+        Self {
+            inner: Rc::new(RefCell::new(ListInner {
+                parent,
+                anchor,
+                key,
+                build,
+                node: |c| c.as_ref().dom_element(),
+                children,
+            })),
+        }
+    }
+
+    /// changes the list to have a child for each of `items`, in order
+    pub fn set_items(&self, items: &[T]) -> Result<()> {
+        let mut changed = Vec::new();
+        let result = self.update(items, &mut changed);
+        // the list is not borrowed anymore, the hooks can use it
+        for node in changed.iter() {
+            lifecycle::check(node);
+        }
+        result
+    }
+
+    /// updates the children and the dom, the nodes that were added or removed are put in
+    /// `changed`
+    fn update(&self, items: &[T], changed: &mut Vec<RawNode>) -> Result<()> {
+        let mut result = Ok(());
+        let removed = self.reorder(items, changed, &mut result)?;
+        // the list is not borrowed while the unmount hooks run, they can use it
+        for (node, _) in removed.iter() {
+            lifecycle::before_remove(node);
+        }
+        let parent = self.inner.borrow().parent.clone();
+        for (node, _) in removed {
+            result = result.and(remove(&parent, &node));
+            changed.push(node);
+        }
+        result
+    }
+
+    /// changes the children to the ones of `items` and inserts the new and moved children in
+    /// the dom, the first error while inserting is put in `result`. the children that are not
+    /// in the list anymore are returned with their nodes, they are still in the dom.
+    fn reorder(
+        &self,
+        items: &[T],
+        changed: &mut Vec<RawNode>,
+        result: &mut Result<()>,
+    ) -> Result<Vec<(RawNode, C)>> {
+        let mut inner = self.inner.borrow_mut();
+        let inner = &mut *inner;
+
+        let mut old: HashMap<&K, usize> = HashMap::with_capacity(inner.children.len());
+        for (i, (key, _)) in inner.children.iter().enumerate() {
+            old.entry(key).or_insert(i);
+        }
+
+        // all new children are built before anything is changed so an error leaves the list
+        // as it was.
+        let mut keys = Vec::with_capacity(items.len());
+        let mut built = Vec::with_capacity(items.len());
+        let mut old_index = Vec::with_capacity(items.len());
+        for item in items {
+            let key = (inner.key)(item);
+            match old.remove(&key) {
+                Some(i) => {
+                    old_index.push(Some(i));
+                    built.push(None);
+                }
+                None => {
+                    old_index.push(None);
+                    built.push(Some((inner.build)(item)?));
+                }
+            }
+            keys.push(key);
+        }
+        drop(old);
+
+        let mut old_children: Vec<Option<C>> = inner
+            .children
+            .drain(..)
+            .map(|(_, child)| Some(child))
+            .collect();
+        let children: Vec<(K, C)> = keys
+            .into_iter()
+            .zip(built)
+            .zip(old_index.iter())
+            .map(|((key, built), index)| {
+                let child = match index {
+                    Some(i) => old_children[*i].take().expect("old child used once"),
+                    None => built.expect("new child is built"),
+                };
+                (key, child)
+            })
+            .collect();
+
+        // the list is changed before the dom, an error while changing the dom leaves the list
+        // with the children of the items. the first error is returned after all changes.
+        inner.children = children;
+        let removed: Vec<(RawNode, C)> = old_children
+            .into_iter()
+            .flatten()
+            .map(|child| (as_node((inner.node)(&child)).clone(), child))
+            .collect();
+
+        // children in the longest run that is still in order stay where they are, the others
+        // are inserted before the child that follows them.
+        let stable = longest_increasing(&old_index);
+        let mut next = inner.anchor.clone();
+        for ((_, child), stable) in inner.children.iter().zip(stable).rev() {
+            let node = as_node((inner.node)(child));
+            if !stable {
+                let inserted = insert(&inner.parent, node, &next);
+                if result.is_ok() {
+                    *result = inserted;
+                }
+                changed.push(node.clone());
+            }
+            next = node.clone();
+        }
+        Ok(removed)
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.borrow().children.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// calls `f` with the child of the item with `key`
+    pub fn with_child<R>(&self, key: &K, f: impl FnOnce(&C) -> R) -> Option<R> {
+        let inner = self.inner.borrow();
+        inner
+            .children
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, child)| f(child))
+    }
+
+    /// the keys of the items in the list, in order
+    pub fn keys(&self) -> Vec<K>
+    where
+        K: Clone,
+    {
+        let inner = self.inner.borrow();
+        inner.children.iter().map(|(k, _)| k.clone()).collect()
+    }
+}
+
+/// removes the child `node` from `parent`
+fn remove(parent: &RawElement, node: &RawNode) -> Result<()> {
+    parent.remove_child(node)?;
+    Ok(())
+}
+
+/// inserts `node` in `parent` before `next`
+fn insert(parent: &RawElement, node: &RawNode, next: &RawNode) -> Result<()> {
+    parent.insert_before(node, Some(next))?;
+    Ok(())
+}
+
+/// for every value whether it is part of a longest strictly increasing subsequence, `None`
+/// values are never part of it.
+fn longest_increasing(values: &[Option<usize>]) -> Vec<bool> {
+    // `tails[n]` is the index of the smallest value that ends an increasing run of `n + 1`
+    let mut tails: Vec<usize> = Vec::new();
+    let mut previous: Vec<Option<usize>> = vec![None; values.len()];
+    for (i, value) in values.iter().enumerate() {
+        let value = match value {
+            Some(value) => *value,
+            None => continue,
+        };
+        let n = tails.partition_point(|&t| values[t] < Some(value));
+        if n > 0 {
+            previous[i] = Some(tails[n - 1]);
+        }
+        if n == tails.len() {
+            tails.push(i);
+        } else {
+            tails[n] = i;
+        }
+    }
+    let mut stable = vec![false; values.len()];
+    let mut current = tails.last().copied();
+    while let Some(i) = current {
+        stable[i] = true;
+        current = previous[i];
+    }
+    stable
+}
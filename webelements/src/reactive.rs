@@ -0,0 +1,218 @@
+//! Values that notify subscribers when they change, used to keep elements in sync with state
+//! through [`Element::bind_text`](crate::Element::bind_text) and the other bind methods.
+
+use std::{
+    cell::{Cell, RefCell},
+    fmt::Debug,
+    rc::{Rc, Weak},
+};
+
+type Subscriber<T> = Rc<RefCell<dyn FnMut(&T)>>;
+
+struct SignalInner<T> {
+    value: RefCell<T>,
+    subscribers: RefCell<Vec<(usize, Subscriber<T>)>>,
+    next_id: Cell<usize>,
+}
+
+/// A value that can be read and subscribed to.
+pub trait Observable<T> {
+    /// calls `f` with the current value
+    fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R;
+
+    /// calls `f` with the new value every time the value changes, until the returned
+    /// subscription is dropped.
+    fn subscribe(&self, f: impl FnMut(&T) + 'static) -> Subscription;
+
+    fn get(&self) -> T
+    where
+        T: Clone,
+    {
+        self.with(T::clone)
+    }
+}
+
+/// A value that notifies its subscribers when it is set. Cloning a signal gives a new handle
+/// to the same value.
+///
+/// setting a signal from one of its own subscribers will panic.
+pub struct Signal<T> {
+    inner: Rc<SignalInner<T>>,
+}
+
+impl<T> Clone for Signal<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: Debug> Debug for Signal<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Signal")
+            .field(&self.inner.value.borrow())
+            .finish()
+    }
+}
+
+impl<T: Default + 'static> Default for Signal<T> {
+    fn default() -> Self {
+        Signal::new(T::default())
+    }
+}
+
+impl<T: 'static> Signal<T> {
+    pub fn new(value: T) -> Signal<T> {
+        Signal {
+            inner: Rc::new(SignalInner {
+                value: RefCell::new(value),
+                subscribers: RefCell::new(Vec::new()),
+                next_id: Cell::new(0),
+            }),
+        }
+    }
+
+    pub fn set(&self, value: T) {
+        *self.inner.value.borrow_mut() = value;
+        self.notify();
+    }
+
+    /// changes the value in place and notifies the subscribers
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        f(&mut self.inner.value.borrow_mut());
+        self.notify();
+    }
+
+    fn notify(&self) {
+        // the list is copied so subscribers can subscribe and unsubscribe while notified
+        let subscribers: Vec<Subscriber<T>> = self
+            .inner
+            .subscribers
+            .borrow()
+            .iter()
+            .map(|(_, s)| s.clone())
+            .collect();
+        let value = self.inner.value.borrow();
+        for subscriber in subscribers {
+            (subscriber.borrow_mut())(&value);
+        }
+    }
+
+    /// a memo that holds `f` applied to the value of this signal
+    pub fn map<U>(&self, f: impl Fn(&T) -> U + 'static) -> Memo<U>
+    where
+        U: PartialEq + 'static,
+    {
+        Memo::new(self, f)
+    }
+}
+
+impl<T: 'static> Observable<T> for Signal<T> {
+    fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.inner.value.borrow())
+    }
+
+    fn subscribe(&self, f: impl FnMut(&T) + 'static) -> Subscription {
+        let id = self.inner.next_id.get();
+        self.inner.next_id.set(id + 1);
+        let subscriber: Subscriber<T> = Rc::new(RefCell::new(f));
+        self.inner.subscribers.borrow_mut().push((id, subscriber));
+        let inner: Weak<SignalInner<T>> = Rc::downgrade(&self.inner);
+        Subscription {
+            unsubscribe: Some(Box::new(move || {
+                if let Some(inner) = inner.upgrade() {
+                    inner.subscribers.borrow_mut().retain(|(i, _)| *i != id);
+                }
+            })),
+        }
+    }
+}
+
+/// A value derived from another observable. Subscribers are only notified when the derived
+/// value changes.
+pub struct Memo<T> {
+    signal: Signal<T>,
+    _source: Rc<Subscription>,
+}
+
+impl<T> Clone for Memo<T> {
+    fn clone(&self) -> Self {
+        Self {
+            signal: self.signal.clone(),
+            _source: self._source.clone(),
+        }
+    }
+}
+
+impl<T: Debug> Debug for Memo<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Memo")
+            .field(&self.signal.inner.value.borrow())
+            .finish()
+    }
+}
+
+impl<T: PartialEq + 'static> Memo<T> {
+    pub fn new<S>(source: &impl Observable<S>, f: impl Fn(&S) -> T + 'static) -> Memo<T> {
+        let signal = Signal::new(source.with(&f));
+        let target = signal.clone();
+        let subscription = source.subscribe(move |value| {
+            let value = f(value);
+            if target.with(|current| *current != value) {
+                target.set(value);
+            }
+        });
+        Memo {
+            signal,
+            _source: Rc::new(subscription),
+        }
+    }
+}
+
+impl<T: 'static> Observable<T> for Memo<T> {
+    fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.signal.with(f)
+    }
+
+    /// the subscription keeps the memo updating even when all handles to the memo are dropped
+    fn subscribe(&self, f: impl FnMut(&T) + 'static) -> Subscription {
+        let mut subscription = self.signal.subscribe(f);
+        let source = self._source.clone();
+        let unsubscribe = subscription.unsubscribe.take();
+        subscription.unsubscribe = Some(Box::new(move || {
+            drop(source);
+            if let Some(unsubscribe) = unsubscribe {
+                unsubscribe()
+            }
+        }));
+        subscription
+    }
+}
+
+/// Handle to a subscription, the subscriber is removed when it is dropped.
+#[must_use = "the subscription is removed when it is dropped"]
+pub struct Subscription {
+    unsubscribe: Option<Box<dyn FnOnce()>>,
+}
+
+impl Subscription {
+    /// keeps the subscription for as long as the observed value lives
+    pub fn forget(mut self) {
+        self.unsubscribe.take();
+    }
+}
+
+impl Debug for Subscription {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Subscription").finish()
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        if let Some(unsubscribe) = self.unsubscribe.take() {
+            unsubscribe()
+        }
+    }
+}
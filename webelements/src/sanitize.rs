@@ -0,0 +1,395 @@
+//! Allowlist html sanitizer used by [`Element::set_html_sanitized`](crate::Element::set_html_sanitized)
+//! for rich text from sources that can't be trusted completely.
+
+use std::collections::{HashMap, HashSet};
+
+/// elements whose content is dropped together with the element when they are not allowed
+static DROP_CONTENT: &[&str] = &[
+    "script", "style", "iframe", "object", "embed", "template", "noscript", "textarea", "title",
+    "svg", "math",
+];
+
+/// html elements that never have children
+static VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// attributes that hold a url, their scheme has to be allowed
+static URL_ATTRIBUTES: &[&str] = &["href", "src", "action", "formaction", "cite", "poster"];
+
+/// The elements, attributes and url schemes a sanitized piece of html can contain. Everything
+/// else is removed: elements that are not allowed are replaced by their content, or dropped
+/// completely for elements like `script` and `style`. Event handler attributes like `onclick`
+/// are never kept, not even when they are allowed.
+#[derive(Debug, Clone)]
+pub struct Policy {
+    tags: HashSet<String>,
+    /// allowed attributes per tag, `*` for attributes allowed on every allowed tag
+    attributes: HashMap<String, HashSet<String>>,
+    schemes: HashSet<String>,
+}
+
+impl Default for Policy {
+    /// text formatting, lists, links and images with `http`, `https` and `mailto` urls
+    fn default() -> Self {
+        Policy::empty()
+            .allow_tags(&[
+                "a",
+                "abbr",
+                "b",
+                "blockquote",
+                "br",
+                "code",
+                "del",
+                "em",
+                "h1",
+                "h2",
+                "h3",
+                "h4",
+                "h5",
+                "h6",
+                "hr",
+                "i",
+                "img",
+                "ins",
+                "kbd",
+                "li",
+                "mark",
+                "ol",
+                "p",
+                "pre",
+                "q",
+                "s",
+                "small",
+                "span",
+                "strong",
+                "sub",
+                "sup",
+                "u",
+                "ul",
+            ])
+            .allow_attributes("*", &["title", "lang", "dir"])
+            .allow_attributes("a", &["href"])
+            .allow_attributes("img", &["src", "alt", "width", "height"])
+            .allow_schemes(&["http", "https", "mailto"])
+    }
+}
+
+impl Policy {
+    /// a policy that allows nothing, only the text is kept
+    pub fn empty() -> Self {
+        Policy {
+            tags: HashSet::new(),
+            attributes: HashMap::new(),
+            schemes: HashSet::new(),
+        }
+    }
+
+    pub fn allow_tags(mut self, tags: &[&str]) -> Self {
+        self.tags
+            .extend(tags.iter().map(|t| t.to_ascii_lowercase()));
+        self
+    }
+
+    /// allows `attributes` on `tag`, or on every allowed tag when `tag` is `*`
+    pub fn allow_attributes(mut self, tag: &str, attributes: &[&str]) -> Self {
+        self.attributes
+            .entry(tag.to_ascii_lowercase())
+            .or_default()
+            .extend(attributes.iter().map(|a| a.to_ascii_lowercase()));
+        self
+    }
+
+    /// allows urls with `schemes`, urls without a scheme are always allowed
+    pub fn allow_schemes(mut self, schemes: &[&str]) -> Self {
+        self.schemes
+            .extend(schemes.iter().map(|s| s.to_ascii_lowercase()));
+        self
+    }
+
+    fn allows_attribute(&self, tag: &str, attribute: &str) -> bool {
+        if attribute.starts_with("on") {
+            return false;
+        }
+        [tag, "*"].iter().any(|t| {
+            self.attributes
+                .get(*t)
+                .is_some_and(|a| a.contains(attribute))
+        })
+    }
+
+    fn allows_url(&self, url: &str) -> bool {
+        // browsers ignore whitespace and control characters in the scheme
+        let url: String = url
+            .chars()
+            .filter(|c| !c.is_whitespace() && !c.is_control())
+            .collect();
+        match url.find([':', '/', '?', '#']) {
+            Some(i) if url[i..].starts_with(':') => {
+                self.schemes.contains(&url[..i].to_ascii_lowercase())
+            }
+            _ => true,
+        }
+    }
+
+    /// the html with everything that is not allowed removed
+    pub fn sanitize(&self, html: &str) -> String {
+        let mut out = String::with_capacity(html.len());
+        // the allowed elements that are open, closed at the end if the html does not
+        let mut open: Vec<String> = Vec::new();
+        let mut rest = html;
+        while let Some(start) = rest.find('<') {
+            push_text(&mut out, &rest[..start]);
+            rest = &rest[start..];
+            let tag = match Tag::parse(rest) {
+                Some((tag, len)) => {
+                    rest = &rest[len..];
+                    tag
+                }
+                None => {
+                    // a `<` that does not start a tag is text
+                    out.push_str("&lt;");
+                    rest = &rest[1..];
+                    continue;
+                }
+            };
+            match tag {
+                Tag::Comment => {}
+                Tag::Open {
+                    name,
+                    attributes,
+                    self_closing,
+                } => {
+                    if self.tags.contains(&name) {
+                        out.push('<');
+                        out.push_str(&name);
+                        for (key, value) in attributes {
+                            if !self.allows_attribute(&name, &key) {
+                                continue;
+                            }
+                            let value = decode_entities(&value);
+                            if URL_ATTRIBUTES.contains(&key.as_str()) && !self.allows_url(&value) {
+                                continue;
+                            }
+                            out.push(' ');
+                            out.push_str(&key);
+                            out.push_str("=\"");
+                            push_escaped(&mut out, &value);
+                            out.push('"');
+                        }
+                        out.push('>');
+                        if !self_closing && !VOID_ELEMENTS.contains(&name.as_str()) {
+                            open.push(name);
+                        }
+                    } else if !self_closing && DROP_CONTENT.contains(&name.as_str()) {
+                        rest = skip_content(rest, &name);
+                    }
+                }
+                Tag::Close { name } => {
+                    if let Some(i) = open.iter().rposition(|n| *n == name) {
+                        for name in open.drain(i..).rev() {
+                            out.push_str("</");
+                            out.push_str(&name);
+                            out.push('>');
+                        }
+                    }
+                }
+            }
+        }
+        push_text(&mut out, rest);
+        for name in open.into_iter().rev() {
+            out.push_str("</");
+            out.push_str(&name);
+            out.push('>');
+        }
+        out
+    }
+}
+
+enum Tag {
+    Open {
+        name: String,
+        attributes: Vec<(String, String)>,
+        self_closing: bool,
+    },
+    Close {
+        name: String,
+    },
+    Comment,
+}
+
+impl Tag {
+    /// the tag at the start of `html` and its length in bytes
+    fn parse(html: &str) -> Option<(Tag, usize)> {
+        let bytes = html.as_bytes();
+        if let Some(comment) = html.strip_prefix("<!--") {
+            let len = comment.find("-->").map_or(html.len(), |i| i + 7);
+            return Some((Tag::Comment, len));
+        }
+        if html.starts_with("<!") || html.starts_with("<?") {
+            let len = html.find('>').map_or(html.len(), |i| i + 1);
+            return Some((Tag::Comment, len));
+        }
+        let closing = bytes.get(1) == Some(&b'/');
+        let mut i = if closing { 2 } else { 1 };
+        if !bytes.get(i).is_some_and(u8::is_ascii_alphabetic) {
+            return None;
+        }
+        let name_start = i;
+        while i < bytes.len() && !is_tag_separator(bytes[i]) {
+            i += 1;
+        }
+        let name = html[name_start..i].to_ascii_lowercase();
+
+        let mut attributes = Vec::new();
+        let mut self_closing = false;
+        loop {
+            while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            match bytes.get(i) {
+                None => return None,
+                Some(b'>') => {
+                    i += 1;
+                    break;
+                }
+                Some(b'/') => {
+                    self_closing = true;
+                    i += 1;
+                    continue;
+                }
+                _ => {}
+            }
+            self_closing = false;
+            let key_start = i;
+            while i < bytes.len() && !is_tag_separator(bytes[i]) && bytes[i] != b'=' {
+                i += 1;
+            }
+            let key = html[key_start..i].to_ascii_lowercase();
+            while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                i += 1;
+            }
+            let mut value = String::new();
+            if bytes.get(i) == Some(&b'=') {
+                i += 1;
+                while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                    i += 1;
+                }
+                match bytes.get(i) {
+                    Some(&quote) if quote == b'"' || quote == b'\'' => {
+                        let end = html[i + 1..].find(quote as char)? + i + 1;
+                        value = html[i + 1..end].to_owned();
+                        i = end + 1;
+                    }
+                    _ => {
+                        let start = i;
+                        while i < bytes.len() && !is_tag_separator(bytes[i]) {
+                            i += 1;
+                        }
+                        value = html[start..i].to_owned();
+                    }
+                }
+            }
+            if !key.is_empty() {
+                attributes.push((key, value));
+            }
+        }
+        let tag = if closing {
+            Tag::Close { name }
+        } else {
+            Tag::Open {
+                name,
+                attributes,
+                self_closing,
+            }
+        };
+        Some((tag, i))
+    }
+}
+
+fn is_tag_separator(byte: u8) -> bool {
+    byte.is_ascii_whitespace() || byte == b'>' || byte == b'/'
+}
+
+/// the rest of the html after the closing tag of `name`
+fn skip_content<'a>(html: &'a str, name: &str) -> &'a str {
+    let close = format!("</{}", name);
+    let lower = html.to_ascii_lowercase();
+    match lower.find(&close) {
+        Some(i) => {
+            let after = &html[i..];
+            after.find('>').map_or("", |end| &after[end + 1..])
+        }
+        None => "",
+    }
+}
+
+/// text is kept as it is, entities included, only characters that could start markup are escaped
+fn push_text(out: &mut String, text: &str) {
+    for c in text.chars() {
+        match c {
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            c => out.push(c),
+        }
+    }
+}
+
+fn push_escaped(out: &mut String, value: &str) {
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+}
+
+/// decodes character references so urls are checked the way the browser reads them
+fn decode_entities(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest[1..]
+            .find(|c: char| !c.is_ascii_alphanumeric() && c != '#')
+            .map_or(rest.len(), |i| i + 1);
+        let name = &rest[1..end];
+        let decoded = match name.strip_prefix('#') {
+            Some(number) => match number.strip_prefix(|c| c == 'x' || c == 'X') {
+                Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                None => number.parse::<u32>().ok(),
+            }
+            .and_then(char::from_u32),
+            None => match name {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{a0}'),
+                "colon" => Some(':'),
+                "tab" => Some('\t'),
+                "newline" => Some('\n'),
+                _ => None,
+            },
+        };
+        match decoded {
+            Some(c) => {
+                out.push(c);
+                // the `;` is optional
+                rest = rest[end..].strip_prefix(';').unwrap_or(&rest[end..]);
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
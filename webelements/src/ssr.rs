@@ -0,0 +1,433 @@
+//! In-memory dom used as the backend of [`Element`](crate::Element) when the `ssr` feature is
+//! enabled. The nodes mirror the parts of the `web_sys::Element` api that the crate uses so
+//! elements can be built and serialized without a browser.
+
+use std::{
+    cell::RefCell,
+    fmt::Write,
+    rc::{Rc, Weak},
+};
+
+use crate::Result;
+
+mod selector;
+
+/// html elements that can't have any children and are serialized without a closing tag
+static VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+#[derive(Debug)]
+enum NodeKind {
+    Element {
+        tag: String,
+        namespace: Option<String>,
+        attributes: Vec<(String, String)>,
+    },
+    Text(String),
+    /// html that is serialized as is, created by `set_inner_html`
+    Raw(String),
+    /// the root of the document, nodes under it are connected
+    Document,
+}
+
+#[derive(Debug)]
+struct NodeData {
+    kind: NodeKind,
+    parent: Weak<RefCell<NodeData>>,
+    children: Vec<Node>,
+}
+
+/// A node in the in-memory dom. Cloning a node gives a new handle to the same node.
+#[derive(Debug, Clone)]
+pub struct Node {
+    inner: Rc<RefCell<NodeData>>,
+}
+
+impl PartialEq for Node {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl Node {
+    fn from_kind(kind: NodeKind) -> Node {
+        Node {
+            inner: Rc::new(RefCell::new(NodeData {
+                kind,
+                parent: Weak::new(),
+                children: Vec::new(),
+            })),
+        }
+    }
+
+    pub fn element(tag: &str, namespace: Option<&str>) -> Node {
+        Node::from_kind(NodeKind::Element {
+            tag: tag.to_owned(),
+            namespace: namespace.map(ToOwned::to_owned),
+            attributes: Vec::new(),
+        })
+    }
+
+    pub fn text(text: &str) -> Node {
+        Node::from_kind(NodeKind::Text(text.to_owned()))
+    }
+
+    /// a document with an empty `<head>` and `<body>`
+    pub fn document() -> Node {
+        let document = Node::from_kind(NodeKind::Document);
+        let html = Node::element("html", None);
+        for child in ["head", "body"].iter() {
+            let _ = html.append_child(&Node::element(child, None));
+        }
+        let _ = document.append_child(&html);
+        document
+    }
+
+    /// the tag of an element, or `#text` for text nodes like the `nodeName` in the browser
+    pub fn tag_name(&self) -> String {
+        match &self.inner.borrow().kind {
+            NodeKind::Element { tag, .. } => tag.clone(),
+            NodeKind::Document => "#document".to_owned(),
+            _ => "#text".to_owned(),
+        }
+    }
+
+    pub fn is_element(&self) -> bool {
+        matches!(self.inner.borrow().kind, NodeKind::Element { .. })
+    }
+
+    pub fn is_text(&self) -> bool {
+        matches!(self.inner.borrow().kind, NodeKind::Text(_))
+    }
+
+    pub fn namespace_uri(&self) -> Option<String> {
+        match &self.inner.borrow().kind {
+            NodeKind::Element { namespace, .. } => namespace.clone(),
+            _ => None,
+        }
+    }
+
+    pub fn get_attribute(&self, name: &str) -> Option<String> {
+        match &self.inner.borrow().kind {
+            NodeKind::Element { attributes, .. } => {
+                attributes
+                    .iter()
+                    .find_map(|(k, v)| if k == name { Some(v.clone()) } else { None })
+            }
+            _ => None,
+        }
+    }
+
+    /// like in the browser names that can't be written in html are rejected
+    pub fn set_attribute(&self, name: &str, value: &str) -> Result<()> {
+        let invalid = |c: char| c.is_whitespace() || c.is_control() || "\"'<>/=".contains(c);
+        if name.is_empty() || name.contains(invalid) {
+            return Err(crate::Error::JsError(crate::JsError {
+                name: Some("InvalidCharacterError".to_owned()),
+                message: format!("`{}` is not a valid attribute name", name),
+                stack: None,
+                value: None,
+            }));
+        }
+        if let NodeKind::Element { attributes, .. } = &mut self.inner.borrow_mut().kind {
+            match attributes.iter_mut().find(|(k, _)| k == name) {
+                Some((_, v)) => *v = value.to_owned(),
+                None => attributes.push((name.to_owned(), value.to_owned())),
+            }
+        }
+        Ok(())
+    }
+
+    pub fn remove_attribute(&self, name: &str) -> Result<()> {
+        if let NodeKind::Element { attributes, .. } = &mut self.inner.borrow_mut().kind {
+            attributes.retain(|(k, _)| k != name);
+        }
+        Ok(())
+    }
+
+    pub fn parent_node(&self) -> Option<Node> {
+        self.inner
+            .borrow()
+            .parent
+            .upgrade()
+            .map(|inner| Node { inner })
+    }
+
+    pub fn child_nodes(&self) -> Vec<Node> {
+        self.inner.borrow().children.clone()
+    }
+
+    pub fn first_child(&self) -> Option<Node> {
+        self.inner.borrow().children.first().cloned()
+    }
+
+    /// the children of this node that are elements
+    pub fn children(&self) -> Vec<Node> {
+        self.inner
+            .borrow()
+            .children
+            .iter()
+            .filter(|c| c.is_element())
+            .cloned()
+            .collect()
+    }
+
+    /// appends `child` as the last child of this node, like in the browser it is first
+    /// removed from its current parent.
+    pub fn append_child(&self, child: &Node) -> Result<Node> {
+        self.check_insert(child)?;
+        child.detach();
+        child.inner.borrow_mut().parent = Rc::downgrade(&self.inner);
+        self.inner.borrow_mut().children.push(child.clone());
+        Ok(child.clone())
+    }
+
+    /// inserts `node` before `child`, or as the last child when `child` is `None`
+    pub fn insert_before(&self, node: &Node, child: Option<&Node>) -> Result<Node> {
+        let child = match child {
+            Some(child) => child,
+            None => return self.append_child(node),
+        };
+        self.check_insert(node)?;
+        if !self.inner.borrow().children.contains(child) {
+            return Err(crate::Error::NotFound);
+        }
+        if child == node {
+            return Ok(node.clone());
+        }
+        // the node is only detached once it is sure it can be inserted
+        node.detach();
+        let mut data = self.inner.borrow_mut();
+        let index = data
+            .children
+            .iter()
+            .position(|c| c == child)
+            .expect("the child is checked above");
+        data.children.insert(index, node.clone());
+        node.inner.borrow_mut().parent = Rc::downgrade(&self.inner);
+        Ok(node.clone())
+    }
+
+    /// like in the browser a node can't be inserted in itself or one of its descendants
+    fn check_insert(&self, node: &Node) -> Result<()> {
+        if node.contains(Some(self)) {
+            return Err(crate::Error::JsError(crate::JsError {
+                name: Some("HierarchyRequestError".to_owned()),
+                message: "the new child contains the parent".to_owned(),
+                stack: None,
+                value: None,
+            }));
+        }
+        Ok(())
+    }
+
+    pub fn remove_child(&self, child: &Node) -> Result<Node> {
+        if child.parent_node().as_ref() != Some(self) {
+            return Err(crate::Error::NotFound);
+        }
+        child.detach();
+        Ok(child.clone())
+    }
+
+    /// whether the node is in a document
+    pub fn is_connected(&self) -> bool {
+        let mut node = self.clone();
+        while let Some(parent) = node.parent_node() {
+            node = parent;
+        }
+        let connected = matches!(node.inner.borrow().kind, NodeKind::Document);
+        connected
+    }
+
+    /// the first element below this node that matches `selector`. only tags, `*`, `#id`,
+    /// `.class`, `[attr]` and `[attr=value]` with the descendant and `>` combinators are
+    /// supported
+    pub fn query_selector(&self, selector: &str) -> Result<Option<Node>> {
+        Ok(self.query_selector_all(selector)?.into_iter().next())
+    }
+
+    /// the elements below this node that match `selector` in document order
+    pub fn query_selector_all(&self, selector: &str) -> Result<Vec<Node>> {
+        let selector = selector::Selector::parse(selector)?;
+        let mut found = Vec::new();
+        self.descendants(&mut |node| {
+            if selector.matches(node) {
+                found.push(node.clone());
+            }
+        });
+        Ok(found)
+    }
+
+    fn descendants(&self, f: &mut impl FnMut(&Node)) {
+        for child in self.child_nodes() {
+            f(&child);
+            child.descendants(f);
+        }
+    }
+
+    /// whether `other` is this node or one of its descendants
+    pub fn contains(&self, other: Option<&Node>) -> bool {
+        let mut node = other.cloned();
+        while let Some(n) = node {
+            if n == *self {
+                return true;
+            }
+            node = n.parent_node();
+        }
+        false
+    }
+
+    pub fn next_sibling(&self) -> Option<Node> {
+        let parent = self.parent_node()?;
+        let data = parent.inner.borrow();
+        let index = data.children.iter().position(|c| c == self)?;
+        data.children.get(index + 1).cloned()
+    }
+
+    fn detach(&self) {
+        if let Some(parent) = self.parent_node() {
+            parent
+                .inner
+                .borrow_mut()
+                .children
+                .retain(|c| !Rc::ptr_eq(&c.inner, &self.inner));
+        }
+        self.inner.borrow_mut().parent = Weak::new();
+    }
+
+    fn clear_children(&self) {
+        let children = std::mem::take(&mut self.inner.borrow_mut().children);
+        for child in children {
+            child.inner.borrow_mut().parent = Weak::new();
+        }
+    }
+
+    pub fn set_inner_html(&self, html: &str) {
+        self.clear_children();
+        if !html.is_empty() {
+            let raw = Node::from_kind(NodeKind::Raw(html.to_owned()));
+            let _ = self.append_child(&raw);
+        }
+    }
+
+    pub fn set_text_content(&self, text: Option<&str>) {
+        self.clear_children();
+        if let Some(text) = text.filter(|t| !t.is_empty()) {
+            let _ = self.append_child(&Node::text(text));
+        }
+    }
+
+    /// changes the text of a text node
+    pub fn set_data(&self, text: &str) {
+        if let NodeKind::Text(data) = &mut self.inner.borrow_mut().kind {
+            *data = text.to_owned();
+        }
+    }
+
+    /// splits a text node at `offset` in utf-16 code units, the text after it is moved to a new
+    /// node that is inserted after this one
+    pub fn split_text(&self, offset: u32) -> Result<Node> {
+        let rest = match &mut self.inner.borrow_mut().kind {
+            NodeKind::Text(data) => {
+                let mut units = 0;
+                let index = data
+                    .char_indices()
+                    .find(|(_, c)| {
+                        units += c.len_utf16();
+                        units > offset as usize
+                    })
+                    .map_or(data.len(), |(i, _)| i);
+                if units < offset as usize {
+                    return Err(crate::Error::js_str("offset is larger than the text"));
+                }
+                data.split_off(index)
+            }
+            _ => return Err(crate::Error::js_str("only text nodes can be split")),
+        };
+        let node = Node::text(&rest);
+        if let Some(parent) = self.parent_node() {
+            parent.insert_before(&node, self.next_sibling().as_ref())?;
+        }
+        Ok(node)
+    }
+
+    pub fn text_content(&self) -> String {
+        match &self.inner.borrow().kind {
+            NodeKind::Text(text) => text.clone(),
+            // raw html is not parsed so the best we can do is return it as is
+            NodeKind::Raw(html) => html.clone(),
+            NodeKind::Element { .. } | NodeKind::Document => self
+                .inner
+                .borrow()
+                .children
+                .iter()
+                .map(|c| c.text_content())
+                .collect(),
+        }
+    }
+
+    pub fn inner_html(&self) -> String {
+        let mut html = String::new();
+        for child in self.inner.borrow().children.iter() {
+            child.write_html(&mut html);
+        }
+        html
+    }
+
+    pub fn outer_html(&self) -> String {
+        let mut html = String::new();
+        self.write_html(&mut html);
+        html
+    }
+
+    fn write_html(&self, out: &mut String) {
+        let data = self.inner.borrow();
+        match &data.kind {
+            NodeKind::Text(text) => out.push_str(&escape(text, false)),
+            NodeKind::Raw(html) => out.push_str(html),
+            NodeKind::Document => {
+                out.push_str("<!DOCTYPE html>");
+                for child in data.children.iter() {
+                    child.write_html(out);
+                }
+            }
+            NodeKind::Element {
+                tag,
+                namespace,
+                attributes,
+            } => {
+                let _ = write!(out, "<{}", tag);
+                for (k, v) in attributes.iter() {
+                    let _ = write!(out, " {}=\"{}\"", k, escape(v, true));
+                }
+                if namespace.is_none() && VOID_ELEMENTS.contains(&tag.as_str()) {
+                    out.push('>');
+                } else if namespace.is_some() && data.children.is_empty() {
+                    out.push_str("/>");
+                } else {
+                    out.push('>');
+                    for child in data.children.iter() {
+                        child.write_html(out);
+                    }
+                    let _ = write!(out, "</{}>", tag);
+                }
+            }
+        }
+    }
+}
+
+fn escape(text: &str, attribute: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' if attribute => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,247 @@
+//! The css selectors supported by `query_selector` on the server: tags, `*`, `#id`, `.class`,
+//! `[attr]` and `[attr=value]`, combined with the descendant and `>` combinators and listed
+//! with `,`.
+
+use std::{iter::Peekable, str::Chars};
+
+use super::Node;
+use crate::{Error, JsError, Result};
+
+/// the parts of a selector that all have to match the same element, like `li.item[data-id]`
+#[derive(Debug, Default)]
+struct Compound {
+    tag: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+    attributes: Vec<(String, Option<String>)>,
+}
+
+impl Compound {
+    fn matches(&self, node: &Node) -> bool {
+        if !node.is_element() {
+            return false;
+        }
+        if let Some(tag) = &self.tag {
+            if !node.tag_name().eq_ignore_ascii_case(tag) {
+                return false;
+            }
+        }
+        if self.id.is_some() && node.get_attribute("id") != self.id {
+            return false;
+        }
+        let class = node.get_attribute("class").unwrap_or_default();
+        if !self
+            .classes
+            .iter()
+            .all(|c| class.split_whitespace().any(|n| n == c))
+        {
+            return false;
+        }
+        self.attributes
+            .iter()
+            .all(|(name, value)| match (node.get_attribute(name), value) {
+                (Some(_), None) => true,
+                (Some(actual), Some(value)) => actual == *value,
+                (None, _) => false,
+            })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Combinator {
+    /// `a b`
+    Descendant,
+    /// `a > b`
+    Child,
+}
+
+/// A parsed selector list.
+#[derive(Debug)]
+pub(crate) struct Selector {
+    /// the compounds of each selector from right to left, with the combinator that joins
+    /// them to the compound on their left
+    list: Vec<Vec<(Compound, Combinator)>>,
+}
+
+impl Selector {
+    pub(crate) fn parse(selector: &str) -> Result<Selector> {
+        let mut parser = Parser {
+            chars: selector.chars().peekable(),
+        };
+        parser.list().ok_or_else(|| {
+            Error::JsError(JsError {
+                name: Some("SyntaxError".to_owned()),
+                message: format!("`{}` is not a supported selector", selector),
+                stack: None,
+                value: None,
+            })
+        })
+    }
+
+    pub(crate) fn matches(&self, node: &Node) -> bool {
+        self.list.iter().any(|compounds| matches(compounds, node))
+    }
+}
+
+fn matches(compounds: &[(Compound, Combinator)], node: &Node) -> bool {
+    let ((compound, combinator), rest) = match compounds.split_first() {
+        Some(first) => first,
+        None => return true,
+    };
+    if !compound.matches(node) {
+        return false;
+    }
+    if rest.is_empty() {
+        return true;
+    }
+    let parent = node.parent_node();
+    match combinator {
+        Combinator::Child => parent.is_some_and(|p| matches(rest, &p)),
+        Combinator::Descendant => {
+            let mut ancestor = parent;
+            while let Some(node) = ancestor {
+                if matches(rest, &node) {
+                    return true;
+                }
+                ancestor = node.parent_node();
+            }
+            false
+        }
+    }
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn list(&mut self) -> Option<Selector> {
+        let mut list = Vec::new();
+        loop {
+            self.whitespace();
+            list.push(self.complex()?);
+            match self.chars.next() {
+                Some(',') => continue,
+                None => return Some(Selector { list }),
+                Some(_) => return None,
+            }
+        }
+    }
+
+    /// compounds and combinators up to a `,` or the end
+    fn complex(&mut self) -> Option<Vec<(Compound, Combinator)>> {
+        // the first compound has nothing on its left, its combinator is not used
+        let mut compounds = vec![(self.compound()?, Combinator::Descendant)];
+        loop {
+            let whitespace = self.whitespace();
+            let combinator = match self.chars.peek() {
+                None | Some(',') => break,
+                Some('>') => {
+                    self.chars.next();
+                    self.whitespace();
+                    Combinator::Child
+                }
+                Some(_) if whitespace => Combinator::Descendant,
+                Some(_) => return None,
+            };
+            compounds.push((self.compound()?, combinator));
+        }
+        // matched from the element itself to its ancestors
+        compounds.reverse();
+        Some(compounds)
+    }
+
+    fn compound(&mut self) -> Option<Compound> {
+        let mut compound = Compound::default();
+        let mut empty = true;
+        match self.chars.peek() {
+            Some('*') => {
+                self.chars.next();
+                empty = false;
+            }
+            Some(c) if is_name(*c) => {
+                compound.tag = Some(self.name()?);
+                empty = false;
+            }
+            _ => {}
+        }
+        loop {
+            match self.chars.peek() {
+                Some('#') => {
+                    self.chars.next();
+                    compound.id = Some(self.name()?);
+                }
+                Some('.') => {
+                    self.chars.next();
+                    compound.classes.push(self.name()?);
+                }
+                Some('[') => {
+                    self.chars.next();
+                    compound.attributes.push(self.attribute()?);
+                }
+                _ => break,
+            }
+            empty = false;
+        }
+        Some(compound).filter(|_| !empty)
+    }
+
+    /// `name]` or `name=value]` after the `[`
+    fn attribute(&mut self) -> Option<(String, Option<String>)> {
+        self.whitespace();
+        let name = self.name()?;
+        self.whitespace();
+        let value = match self.chars.next()? {
+            ']' => return Some((name, None)),
+            '=' => {
+                self.whitespace();
+                match self.chars.peek()? {
+                    '"' | '\'' => self.string()?,
+                    _ => self.name()?,
+                }
+            }
+            _ => return None,
+        };
+        self.whitespace();
+        match self.chars.next()? {
+            ']' => Some((name, Some(value))),
+            _ => None,
+        }
+    }
+
+    /// a quoted string, a `\` escapes the character after it
+    fn string(&mut self) -> Option<String> {
+        let quote = self.chars.next()?;
+        let mut value = String::new();
+        loop {
+            match self.chars.next()? {
+                '\\' => value.push(self.chars.next()?),
+                c if c == quote => return Some(value),
+                c => value.push(c),
+            }
+        }
+    }
+
+    fn name(&mut self) -> Option<String> {
+        let mut name = String::new();
+        while let Some(c) = self.chars.peek().copied().filter(|c| is_name(*c)) {
+            name.push(c);
+            self.chars.next();
+        }
+        Some(name).filter(|n| !n.is_empty())
+    }
+
+    /// skips whitespace, returns whether there was any
+    fn whitespace(&mut self) -> bool {
+        let mut skipped = false;
+        while self.chars.peek().is_some_and(|c| c.is_whitespace()) {
+            self.chars.next();
+            skipped = true;
+        }
+        skipped
+    }
+}
+
+fn is_name(c: char) -> bool {
+    c.is_alphanumeric() || c == '-' || c == '_' || !c.is_ascii()
+}
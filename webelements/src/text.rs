@@ -0,0 +1,120 @@
+//! Text nodes, created for the text in templates and with
+//! [`Element::append_text`](crate::Element::append_text).
+
+use std::{cell::RefCell, fmt::Display, rc::Rc};
+
+use crate::{
+    element::RawNode,
+    reactive::{Observable, Subscription},
+    Result,
+};
+
+#[cfg(not(feature = "ssr"))]
+type RawText = web_sys::Text;
+#[cfg(feature = "ssr")]
+type RawText = crate::ssr::Node;
+
+/// A text node in the dom. Cloning it gives a new handle to the same node.
+#[derive(Debug, Clone)]
+pub struct TextNode {
+    node: RawText,
+    /// subscriptions of the signals bound to the text, shared between clones and removed when
+    /// the last clone is dropped
+    bindings: Rc<RefCell<Vec<Subscription>>>,
+}
+
+impl PartialEq for TextNode {
+    fn eq(&self, other: &TextNode) -> bool {
+        self.node == other.node
+    }
+}
+
+impl TextNode {
+    fn wrap(node: RawText) -> TextNode {
+        TextNode {
+            node,
+            bindings: Rc::default(),
+        }
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    pub fn new(text: impl AsRef<str>) -> Result<TextNode> {
+        Ok(TextNode::wrap(
+            crate::document()?.create_text_node(text.as_ref()),
+        ))
+    }
+
+    #[cfg(feature = "ssr")]
+    pub fn new(text: impl AsRef<str>) -> Result<TextNode> {
+        Ok(TextNode::wrap(RawText::text(text.as_ref())))
+    }
+
+    /// the text node if `node` is one
+    #[cfg(not(feature = "ssr"))]
+    pub(crate) fn from_node(node: RawNode) -> Option<TextNode> {
+        use wasm_bindgen::JsCast;
+        node.dyn_into::<RawText>().ok().map(TextNode::wrap)
+    }
+
+    #[cfg(feature = "ssr")]
+    pub(crate) fn from_node(node: RawNode) -> Option<TextNode> {
+        if node.is_text() {
+            Some(TextNode::wrap(node))
+        } else {
+            None
+        }
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    pub fn text(&self) -> String {
+        self.node.data()
+    }
+
+    #[cfg(feature = "ssr")]
+    pub fn text(&self) -> String {
+        self.node.text_content()
+    }
+
+    pub fn set_text(&self, text: impl AsRef<str>) {
+        self.node.set_data(text.as_ref())
+    }
+
+    /// keeps the text equal to the value of `signal` until the last clone of this node is
+    /// dropped, the `updated` hook of the component the node is in is called when it changes
+    pub fn bind_text<T: Display + 'static>(&self, signal: &impl Observable<T>) {
+        // the subscription holds a handle without the bindings, so it doesn't keep itself alive
+        let this = TextNode::wrap(self.node.clone());
+        signal.with(|value| this.set_text(value.to_string()));
+        let subscription = signal.subscribe(move |value| {
+            this.set_text(value.to_string());
+            crate::lifecycle::updated(this.as_node());
+        });
+        self.bindings.borrow_mut().push(subscription);
+    }
+
+    /// the length of the text in utf-16 code units
+    #[cfg(not(feature = "ssr"))]
+    pub(crate) fn len(&self) -> u32 {
+        self.node.length()
+    }
+
+    #[cfg(feature = "ssr")]
+    pub(crate) fn len(&self) -> u32 {
+        self.node.text_content().encode_utf16().count() as u32
+    }
+
+    /// splits the node at `offset` in utf-16 code units, returns the node with the text after it
+    pub(crate) fn split(&self, offset: u32) -> Result<TextNode> {
+        Ok(TextNode::wrap(self.node.split_text(offset)?))
+    }
+
+    #[cfg(not(feature = "ssr"))]
+    pub(crate) fn as_node(&self) -> &RawNode {
+        self.node.as_ref()
+    }
+
+    #[cfg(feature = "ssr")]
+    pub(crate) fn as_node(&self) -> &RawNode {
+        &self.node
+    }
+}
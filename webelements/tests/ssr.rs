@@ -0,0 +1,1088 @@
+#![cfg(feature = "ssr")]
+
+use webelements::{
+    document, elem, event, render_to_string, sanitize::Policy, we_builder, AnyElement, Conditional,
+    Context, Element, ElementList, Error, Form, JsError, Observable, Signal, WeForm, WebElement,
+    WebElementBuilder,
+};
+
+#[we_builder(
+    <div class="my-element" attr="value" we_unchecked="attr">
+        <div class="repeated" we_field="repeated" we_repeat=2 />
+    </div>
+)]
+#[derive(Debug, Clone, WebElement)]
+struct MyElement {}
+
+#[we_builder(
+    <div class="other" id="other">
+        <p>text</p>
+        <input we_field="input" type="text" />
+        <MyElement we_field="elem" we_element />
+        <svg><path d="M 0 0" /></svg>
+    </div>
+)]
+#[derive(Debug, Clone, WebElement)]
+struct OtherElement {}
+
+#[test]
+fn test_ssr_render() {
+    assert_eq!(
+        render_to_string::<MyElement>().unwrap(),
+        r#"<div class="my-element" attr="value"><div class="repeated"></div><div class="repeated"></div></div>"#
+    );
+}
+
+#[test]
+fn test_ssr_nested() {
+    let el = OtherElement::build().unwrap();
+    el.input.set_value("a \"quoted\" <value>");
+    el.elem.repeated[0].add_class("first");
+    assert_eq!(
+        el.to_html(),
+        concat!(
+            r#"<div class="other" id="other"><p>text</p>"#,
+            r#"<input type="text" value="a &quot;quoted&quot; &lt;value&gt;">"#,
+            r#"<div class="my-element" attr="value"><div class="repeated first"></div><div class="repeated"></div></div>"#,
+            r#"<svg><path d="M 0 0"/></svg></div>"#
+        )
+    );
+}
+
+#[test]
+fn test_ssr_hydrate() {
+    let el = OtherElement::build().unwrap();
+    let hydrated = OtherElement::hydrate(el.dom_element()).unwrap();
+    assert_eq!(hydrated.input.dom_element(), el.input.dom_element());
+    assert_eq!(
+        hydrated.elem.repeated[1].dom_element(),
+        el.elem.repeated[1].dom_element()
+    );
+}
+
+#[test]
+fn test_ssr_hydrate_mismatch() {
+    let el = MyElement::build().unwrap();
+    let err = OtherElement::hydrate(el.dom_element()).unwrap_err();
+    match err {
+        Error::Hydrate(mismatch) => {
+            assert_eq!(mismatch.path, vec!["div[0]", "p[0]"]);
+            assert_eq!(mismatch.expected.as_deref(), Some("p"));
+            assert_eq!(mismatch.found.as_deref(), Some("div"));
+        }
+        err => panic!("unexpected error {:?}", err),
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct LabelProps {
+    class: &'static str,
+    label: String,
+}
+
+#[we_builder(
+    <div we_props="LabelProps" class="label">
+        <span class={props.class} title={props.label.len()}>{props.label}!</span>
+        <p we_repeat=2 data-index={i}>item {i}</p>
+    </div>
+)]
+#[derive(Debug, Clone, WebElement)]
+struct Label {}
+
+#[test]
+fn test_ssr_interpolate() {
+    let el = Label::build_with(LabelProps {
+        class: "big bold",
+        label: "hello".to_owned(),
+    })
+    .unwrap();
+    assert_eq!(
+        el.to_html(),
+        concat!(
+            r#"<div class="label"><span class="big bold" title="5">hello!</span>"#,
+            r#"<p data-index="0">item 0</p><p data-index="1">item 1</p></div>"#
+        )
+    );
+}
+
+#[derive(Debug, Clone, Default)]
+struct CounterProps {
+    start: u8,
+    label: String,
+    step_size: u8,
+}
+
+#[we_builder(
+    <div we_props="CounterProps" class="counter">{props.label}: {props.start}" +"{props.step_size}</div>
+)]
+#[derive(Debug, Clone, WebElement)]
+struct Counter {}
+
+#[we_builder(
+    <div we_props="u8">
+        <Counter we_field="counter" id="first" start=5 label="count" step-size={props} we_element />
+        <Counter we_element />
+    </div>
+)]
+#[derive(Debug, Clone, WebElement)]
+struct Counters {}
+
+#[test]
+fn test_ssr_child_props() {
+    let el = Counters::build_with(2).unwrap();
+    assert_eq!(el.counter.attr("id").as_deref(), Some("first"));
+    assert_eq!(
+        el.to_html(),
+        concat!(
+            r#"<div><div class="counter" id="first">count: 5 +2</div>"#,
+            r#"<div class="counter">: 0 +0</div></div>"#
+        )
+    );
+}
+
+#[we_builder(
+    <div>
+        <button we_on:click="Self::clicked">click</button>
+    </div>
+)]
+#[derive(Debug, Clone, WebElement)]
+struct ClickElement {}
+
+impl ClickElement {
+    fn clicked(&self, _e: web_sys::MouseEvent) {
+        self.add_class("clicked");
+    }
+}
+
+#[test]
+fn test_ssr_on_event() {
+    let el = ClickElement::build().unwrap();
+    let _handle = el.on::<event::Click>(|_| ()).unwrap();
+    assert_eq!(
+        render_to_string::<ClickElement>().unwrap(),
+        "<div><button>click</button></div>"
+    );
+}
+
+#[we_builder(
+    <div we_props="Signal<u32>" we_bind:class:many={props.map(|c| *c > 1)}>
+        <span we_field="count" we_bind:text={props} we_bind:data-count={props}>0</span>
+    </div>
+)]
+#[derive(Debug, Clone, WebElement)]
+struct SignalElement {}
+
+#[test]
+fn test_ssr_signals() {
+    let count = Signal::new(1);
+    let el = SignalElement::build_with(count.clone()).unwrap();
+    assert_eq!(el.to_html(), r#"<div><span data-count="1">1</span></div>"#);
+    count.set(2);
+    assert_eq!(
+        el.to_html(),
+        r#"<div class="many"><span data-count="2">2</span></div>"#
+    );
+
+    let label = count.map(|c| format!("{} items", c));
+    let span = el.count.clone();
+    span.bind_text(&label);
+    count.update(|c| *c += 1);
+    assert_eq!(span.to_html(), r#"<span data-count="3">3 items</span>"#);
+
+    // the bindings are removed with the last handle to the element
+    drop(el);
+    drop(span);
+    count.set(4);
+    assert_eq!(label.get(), "4 items");
+}
+
+#[test]
+fn test_ssr_hydrate_signals() {
+    let rendered = SignalElement::build_with(Signal::new(2)).unwrap();
+    let count = Signal::new(2);
+    let el = SignalElement::hydrate_with(rendered.dom_element(), count.clone()).unwrap();
+    assert_eq!(el.count.dom_element(), rendered.count.dom_element());
+    drop(rendered);
+    count.set(5);
+    assert_eq!(
+        el.to_html(),
+        r#"<div class="many"><span data-count="5">5</span></div>"#
+    );
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Todo {
+    id: u32,
+    text: &'static str,
+}
+
+#[we_builder(
+    <ul we_props="Signal<Vec<Todo>>">
+        <li class="first">first</li>
+        <li we_for="todo in props" we_key="todo.id" data-id={todo.id}>{todo.text}</li>
+        <li class="last">last</li>
+    </ul>
+)]
+#[derive(Debug, Clone, WebElement)]
+struct TodoList {}
+
+#[test]
+fn test_ssr_list() {
+    let todo = |id, text| Todo { id, text };
+    let todos = Signal::new(vec![todo(1, "a"), todo(2, "b")]);
+    let el = TodoList::build_with(todos.clone()).unwrap();
+    let items = |el: &TodoList| {
+        el.dom_element()
+            .children()
+            .into_iter()
+            .map(|c| c.outer_html())
+            .collect::<Vec<_>>()
+            .join("")
+    };
+    assert_eq!(
+        el.to_html(),
+        concat!(
+            r#"<ul><li class="first">first</li><li data-id="1">a</li><li data-id="2">b</li>"#,
+            r#"<li class="last">last</li></ul>"#
+        )
+    );
+    let second = el.dom_element().children()[2].clone();
+    second.set_attribute("class", "kept").unwrap();
+
+    todos.set(vec![todo(3, "c"), todo(2, "changed"), todo(1, "a")]);
+    assert_eq!(
+        items(&el),
+        concat!(
+            r#"<li class="first">first</li><li data-id="3">c</li>"#,
+            r#"<li data-id="2" class="kept">b</li><li data-id="1">a</li><li class="last">last</li>"#
+        )
+    );
+    assert_eq!(el.dom_element().children()[2], second);
+
+    todos.set(vec![todo(1, "a")]);
+    assert_eq!(
+        items(&el),
+        r#"<li class="first">first</li><li data-id="1">a</li><li class="last">last</li>"#
+    );
+}
+
+#[test]
+fn test_ssr_hydrate_list() {
+    let todo = |id, text| Todo { id, text };
+    let rendered = TodoList::build_with(Signal::new(vec![todo(1, "a"), todo(2, "b")])).unwrap();
+    let todos = Signal::new(vec![todo(1, "a"), todo(2, "b")]);
+    let el = TodoList::hydrate_with(rendered.dom_element(), todos.clone()).unwrap();
+    let second = el.dom_element().children()[2].clone();
+    drop(rendered);
+
+    // the rendered elements are the children of the items, only the new item is built
+    todos.update(|todos| todos.push(todo(3, "c")));
+    assert_eq!(
+        el.to_html(),
+        concat!(
+            r#"<ul><li class="first">first</li><li data-id="1">a</li><li data-id="2">b</li>"#,
+            r#"<li data-id="3">c</li><li class="last">last</li></ul>"#
+        )
+    );
+    assert_eq!(el.dom_element().children()[2], second);
+    todos.set(vec![todo(3, "c")]);
+    assert_eq!(el.dom_element().children().len(), 3);
+}
+
+#[we_builder(
+    <div we_props="Vec<u8>">
+        <Counter we_for="n in props" we_key="*n" start={*n} label="n" step-size=1 we_element />
+    </div>
+)]
+#[derive(Debug, Clone, WebElement)]
+struct CounterList {}
+
+#[test]
+fn test_ssr_list_elements() {
+    let el = CounterList::build_with(vec![1, 2]).unwrap();
+    assert_eq!(
+        el.to_html(),
+        r#"<div><div class="counter">n: 1 +1</div><div class="counter">n: 2 +1</div></div>"#
+    );
+
+    let list = ElementList::new(
+        &el.root,
+        |n: &u32| *n,
+        |n| {
+            let item = Element::<elem::Span>::new()?;
+            item.set_text(n.to_string());
+            Ok(item)
+        },
+    )
+    .unwrap();
+    list.set_items(&[3, 4, 5]).unwrap();
+    list.set_items(&[5, 3]).unwrap();
+    assert_eq!(list.keys(), vec![5, 3]);
+    assert_eq!(
+        el.dom_element().inner_html(),
+        concat!(
+            r#"<div class="counter">n: 1 +1</div><div class="counter">n: 2 +1</div>"#,
+            "<span>5</span><span>3</span>"
+        )
+    );
+}
+
+thread_local! {
+    static BEFORE_UNMOUNT: std::cell::RefCell<Option<Box<dyn Fn()>>> = const { std::cell::RefCell::new(None) };
+}
+
+#[we_builder(<li we_props="u32">{props}</li>)]
+#[derive(Debug, Clone, WebElement)]
+#[we(before_unmount = "Self::unmounting")]
+struct Unmounting {}
+
+impl Unmounting {
+    fn unmounting(&self) {
+        BEFORE_UNMOUNT.with(|f| {
+            if let Some(f) = f.borrow().as_ref() {
+                f()
+            }
+        });
+    }
+}
+
+#[test]
+fn test_ssr_list_hooks_use_list() {
+    let parent = Element::<elem::Ul>::new().unwrap();
+    document().unwrap().body().unwrap().append(&parent).unwrap();
+    let list = ElementList::new(&parent, |n: &u32| *n, |n| Unmounting::build_with(*n)).unwrap();
+    list.set_items(&[1, 2, 3]).unwrap();
+
+    // the list is not borrowed while the unmount hooks run
+    let seen = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+    let (hooked, seen_in_hook) = (list.clone(), seen.clone());
+    BEFORE_UNMOUNT.with(|f| {
+        *f.borrow_mut() = Some(Box::new(move || {
+            seen_in_hook.borrow_mut().push(hooked.keys())
+        }))
+    });
+    list.set_items(&[3, 1]).unwrap();
+    BEFORE_UNMOUNT.with(|f| f.borrow_mut().take());
+    assert_eq!(*seen.borrow(), vec![vec![3, 1]]);
+    assert_eq!(parent.to_html(), "<ul><li>3</li><li>1</li></ul>");
+}
+
+#[test]
+fn test_ssr_conditional_hooks_use_conditional() {
+    let parent = Element::<elem::Ul>::new().unwrap();
+    document().unwrap().body().unwrap().append(&parent).unwrap();
+    let conditional = Conditional::new(&parent, || Unmounting::build_with(1)).unwrap();
+    conditional.show().unwrap();
+
+    // the conditional is not borrowed while the unmount hooks run
+    let seen = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+    let (hooked, seen_in_hook) = (conditional.clone(), seen.clone());
+    BEFORE_UNMOUNT.with(|f| {
+        *f.borrow_mut() = Some(Box::new(move || {
+            seen_in_hook.borrow_mut().push(hooked.is_shown())
+        }))
+    });
+    conditional.hide().unwrap();
+    BEFORE_UNMOUNT.with(|f| f.borrow_mut().take());
+    assert_eq!(*seen.borrow(), vec![true]);
+    assert!(!conditional.is_shown());
+    assert_eq!(parent.to_html(), "<ul></ul>");
+}
+
+#[we_builder(
+    <div we_props="Signal<bool>">
+        <p>start</p>
+        <span we_field="open" we_if={props} class="open">open</span>
+        <span class="closed" we_else>closed</span>
+        <p>end</p>
+    </div>
+)]
+#[derive(Debug, Clone, WebElement)]
+struct Toggle {}
+
+#[test]
+fn test_ssr_conditional() {
+    let open = Signal::new(false);
+    let el = Toggle::build_with(open.clone()).unwrap();
+    assert!(!el.open.is_shown());
+    assert_eq!(
+        el.to_html(),
+        r#"<div><p>start</p><span class="closed">closed</span><p>end</p></div>"#
+    );
+    open.set(true);
+    assert_eq!(
+        el.to_html(),
+        r#"<div><p>start</p><span class="open">open</span><p>end</p></div>"#
+    );
+    let span = el.open.get().unwrap();
+    span.add_class("kept");
+    el.open.hide().unwrap();
+    el.open.show().unwrap();
+    assert_eq!(
+        el.open.get().unwrap().attr("class").as_deref(),
+        Some("open kept")
+    );
+}
+
+#[test]
+fn test_ssr_hydrate_conditional() {
+    // both branches are a `<span>`, the condition decides which one was rendered
+    let rendered = Toggle::build_with(Signal::new(false)).unwrap();
+    let open = Signal::new(false);
+    let hydrated = Toggle::hydrate_with(rendered.dom_element(), open.clone()).unwrap();
+    assert!(!hydrated.open.is_shown());
+    drop(rendered);
+
+    open.set(true);
+    assert_eq!(
+        hydrated.to_html(),
+        r#"<div><p>start</p><span class="open">open</span><p>end</p></div>"#
+    );
+    open.set(false);
+    assert_eq!(
+        hydrated.to_html(),
+        r#"<div><p>start</p><span class="closed">closed</span><p>end</p></div>"#
+    );
+    hydrated.open.show().unwrap();
+    assert!(hydrated.open.get().is_some());
+}
+
+#[we_builder(
+    <form>
+        <a we_field="link" href="/start">start</a>
+        <input we_field="check" type="checkbox" />
+        <select we_field="choice">
+            <option value="a">A</option>
+            <option value="b">B</option>
+        </select>
+        <textarea we_field="text" rows=2 />
+    </form>
+)]
+#[derive(Debug, Clone, WebElement)]
+struct Accessors {}
+
+#[test]
+fn test_ssr_accessors() {
+    let el = Accessors::build().unwrap();
+    assert_eq!(el.link.href().as_deref(), Some("/start"));
+    el.link.set_target("_blank").unwrap();
+    // every attribute the template check knows has an accessor
+    assert_eq!(el.link.ping(), None);
+    assert_eq!(el.check.r#type().as_deref(), Some("checkbox"));
+    el.check.set_checked(true);
+    assert!(el.check.checked());
+    // `disabled`, `min` and `max` are properties in the browser, their setters can't fail
+    el.check.set_disabled(true);
+    assert!(el.check.disabled());
+    el.check.set_min(1);
+    assert_eq!(el.check.min(), "1");
+    assert_eq!(el.choice.value(), "a");
+    el.choice.set_value("b");
+    assert_eq!(el.choice.selected_index(), Some(1));
+    assert_eq!(el.text.rows(), Some(2));
+    el.text.set_cols(40).unwrap();
+    el.text.set_value("<b>text</b>");
+    assert_eq!(el.text.value(), "<b>text</b>");
+    assert_eq!(
+        el.to_html(),
+        concat!(
+            r#"<form><a href="/start" target="_blank">start</a>"#,
+            r#"<input type="checkbox" checked="" disabled="" min="1">"#,
+            r#"<select><option value="a">A</option><option value="b" selected="">B</option></select>"#,
+            r#"<textarea rows="2" cols="40">&lt;b&gt;text&lt;/b&gt;</textarea></form>"#
+        )
+    );
+}
+
+#[test]
+fn test_ssr_text_is_escaped() {
+    let el = Element::<elem::Div>::new().unwrap();
+    el.set_text("<img src=x onerror=alert(1)>");
+    assert_eq!(
+        el.to_html(),
+        "<div>&lt;img src=x onerror=alert(1)&gt;</div>"
+    );
+    el.set_inner_html_unchecked("<b>bold</b>");
+    assert_eq!(el.to_html(), "<div><b>bold</b></div>");
+}
+
+#[test]
+fn test_ssr_sanitize() {
+    let policy = Policy::default();
+    let el = Element::<elem::Div>::new().unwrap();
+    el.set_html_sanitized(
+        concat!(
+            r#"<p onclick="steal()">hi <b>there</b><script>alert(1)</script>"#,
+            r#"<a href="javascript:alert(1)" title='t'>x</a>"#,
+            r#"<a href="https://example.com/?a=1&amp;b=2">y</a>"#,
+            r#"<custom-tag>kept text<img src=x onerror=alert(1)>"#,
+        ),
+        &policy,
+    );
+    assert_eq!(
+        el.to_html(),
+        concat!(
+            r#"<div><p>hi <b>there</b><a title="t">x</a>"#,
+            r#"<a href="https://example.com/?a=1&amp;b=2">y</a>"#,
+            r#"kept text<img src="x"></p></div>"#
+        )
+    );
+
+    assert_eq!(
+        policy.sanitize(r#"<a href="&#106;avascript:alert(1)">a</a> 1 < 2"#),
+        "<a>a</a> 1 &lt; 2"
+    );
+    assert_eq!(
+        Policy::empty().sanitize("<p>only <i>text</i></p><style>p {}</style>"),
+        "only text"
+    );
+    let links = Policy::empty()
+        .allow_tags(&["a"])
+        .allow_attributes("a", &["href", "onclick"])
+        .allow_schemes(&["https"]);
+    assert_eq!(
+        links.sanitize(r#"<a href="http://a" onclick="x()">a</a><a href="/b">b</a>"#),
+        r#"<a>a</a><a href="/b">b</a>"#
+    );
+}
+
+#[we_builder(
+    <div we_props="u32">
+        <p>Hello <b>world</b>! it's {props}</p>
+        <p>count: <we_text we_field="count">{props}</we_text> items <span>!</span></p>
+    </div>
+)]
+#[derive(Debug, Clone, WebElement)]
+struct Mixed {}
+
+#[test]
+fn test_ssr_mixed_text() {
+    let el = Mixed::build_with(3).unwrap();
+    assert_eq!(
+        el.to_html(),
+        concat!(
+            r#"<div><p>Hello <b>world</b>! it's 3</p>"#,
+            r#"<p>count: 3 items <span>!</span></p></div>"#
+        )
+    );
+    el.count.set_text("4");
+    assert_eq!(el.count.text(), "4");
+    assert!(el.to_html().contains("<p>count: 4 items <span>"));
+
+    // the rendered text is merged, hydrating splits off the text of the field
+    let dom = el.dom_element().clone();
+    let p = dom.children()[1].clone();
+    let merged = webelements::ssr::Node::text("count: 17 items ");
+    p.insert_before(&merged, p.first_child().as_ref()).unwrap();
+    for node in p.child_nodes().into_iter().skip(1).take(3) {
+        p.remove_child(&node).unwrap();
+    }
+    let hydrated = Mixed::hydrate(&dom).unwrap();
+    assert_eq!(hydrated.count.text(), "17");
+    hydrated.count.set_text("8");
+    assert!(el.to_html().contains("<p>count: 8 items <span>"));
+}
+
+#[test]
+fn test_ssr_error_context() {
+    let el = Element::<elem::Div>::new().unwrap();
+    let err = el.set_attr("bad name", "value").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "setting attribute `bad name` on `<div>`: InvalidCharacterError: `bad name` is not a valid attribute name"
+    );
+    match err.root_cause() {
+        Error::JsError(JsError { name, .. }) => {
+            assert_eq!(name.as_deref(), Some("InvalidCharacterError"))
+        }
+        err => panic!("unexpected error {:?}", err),
+    }
+
+    let err = el
+        .set_attr("data-ok", "value")
+        .and_then(|_| Err::<(), _>(Error::Value))
+        .context("saving")
+        .with_context(|| format!("form {}", 1))
+        .unwrap_err();
+    assert_eq!(err.to_string(), "form 1: saving: invalid value");
+    assert!(matches!(err.root_cause(), Error::Value));
+
+    // the wrapped errors are the sources of the context
+    let mut chain = Vec::new();
+    let mut source: Option<&dyn std::error::Error> = Some(&err);
+    while let Some(error) = source {
+        chain.push(error.to_string());
+        source = error.source();
+    }
+    assert_eq!(
+        chain,
+        vec![
+            "form 1: saving: invalid value",
+            "saving: invalid value",
+            "invalid value"
+        ]
+    );
+}
+
+#[test]
+fn test_ssr_input_value() {
+    let input = Element::<elem::Input>::new().unwrap();
+    input.set_value("12a");
+    let err = input.get_value::<u32>().unwrap_err();
+    match &err {
+        Error::Parse { value, ty, source } => {
+            assert_eq!(value, "12a");
+            assert_eq!(*ty, "u32");
+            assert_eq!(source.to_string(), "invalid digit found in string");
+        }
+        err => panic!("unexpected error {:?}", err),
+    }
+    assert_eq!(
+        err.to_string(),
+        "`12a` is not a valid `u32`: invalid digit found in string"
+    );
+    let source = std::error::Error::source(&err).unwrap();
+    assert_eq!(source.to_string(), "invalid digit found in string");
+    assert!(source.is::<std::num::ParseIntError>());
+    assert_eq!(input.value_as_number(), None);
+
+    input.set_type("number").unwrap();
+    input.set_value("1.5");
+    assert_eq!(input.get_value::<f64>().unwrap(), 1.5);
+    assert_eq!(input.value_as_number(), Some(1.5));
+}
+
+#[we_builder(
+    <form>
+        <input we_field="name" type="text" />
+        <input we_field="age" type="number" />
+        <input we_field="accept" type="checkbox" />
+        <span we_field="name_error" />
+    </form>
+)]
+#[derive(Debug, Clone, WebElement)]
+struct SignupView {}
+
+#[derive(Debug, Clone, Default, PartialEq, WeForm)]
+#[we_form(view = "SignupView", validate = "Self::check")]
+struct Signup {
+    #[we_form(validate = "not_empty")]
+    name: String,
+    age: u32,
+    #[we_form(field = "accept")]
+    terms: bool,
+    #[we_form(skip)]
+    id: u64,
+}
+
+impl Signup {
+    fn check(&self, errors: &mut webelements::FormErrors) {
+        if self.terms && self.age < 18 {
+            errors.add("age", "too young to accept");
+        }
+    }
+}
+
+fn not_empty(value: &str) -> Result<(), &'static str> {
+    if value.trim().is_empty() {
+        Err("can't be empty")
+    } else {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_ssr_form() {
+    let view = SignupView::build().unwrap();
+    let signup = Signup {
+        name: "ann".to_owned(),
+        age: 20,
+        ..Signup::default()
+    };
+    let form = Form::new(&view, signup.clone()).unwrap();
+    assert_eq!(view.age.value(), "20");
+    assert!(!view.accept.checked());
+    view.name_error.bind_text(&form.field_error("name"));
+
+    // errors are shown after a field is changed
+    view.name.set_value(" ");
+    view.age.set_value("abc");
+    assert!(form.errors().get().is_empty());
+    form.update("name");
+    assert_eq!(form.errors().get().get("name"), Some("can't be empty"));
+    assert_eq!(view.name_error.to_html(), "<span>can't be empty</span>");
+    assert_eq!(view.name.attr("aria-invalid").as_deref(), Some("true"));
+    assert_eq!(view.age.attr("aria-invalid"), None);
+
+    // a field that can't be parsed keeps its last value
+    form.update("age");
+    assert_eq!(form.get().age, 20);
+    assert_eq!(
+        form.errors().get().get("age"),
+        Some("invalid digit found in string")
+    );
+
+    view.name.set_value("bob");
+    view.age.set_value("12");
+    view.accept.set_checked(true);
+    let errors = form.submit().unwrap_err();
+    assert_eq!(
+        errors.iter().collect::<Vec<_>>(),
+        vec![("age", "too young to accept")]
+    );
+    assert_eq!(view.name_error.to_html(), "<span></span>");
+    assert_eq!(view.name.attr("aria-invalid"), None);
+
+    view.accept.set_checked(false);
+    let value = form.submit().unwrap();
+    assert_eq!(
+        value,
+        Signup {
+            name: "bob".to_owned(),
+            age: 12,
+            terms: false,
+            id: 0,
+        }
+    );
+
+    form.set(signup);
+    assert_eq!(view.name.value(), "ann");
+    assert!(form.errors().get().is_empty());
+}
+
+thread_local! {
+    static HOOKS: std::cell::RefCell<Vec<&'static str>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+fn hooks() -> Vec<&'static str> {
+    HOOKS.with(|h| h.borrow_mut().drain(..).collect())
+}
+
+#[we_builder(
+    <div class="hooked">
+        <span we_field="count">0</span>
+    </div>
+)]
+#[derive(Debug, Clone)]
+struct Hooked {}
+
+impl WebElement for Hooked {
+    fn init(&mut self) -> webelements::Result<()> {
+        HOOKS.with(|h| h.borrow_mut().push("init"));
+        Ok(())
+    }
+
+    fn mounted(&self) {
+        HOOKS.with(|h| h.borrow_mut().push("mounted"));
+    }
+
+    fn before_unmount(&self) {
+        HOOKS.with(|h| h.borrow_mut().push("before_unmount"));
+    }
+
+    fn unmounted(&self) {
+        HOOKS.with(|h| h.borrow_mut().push("unmounted"));
+    }
+
+    fn updated(&self) {
+        HOOKS.with(|h| h.borrow_mut().push("updated"));
+    }
+
+    fn dropped(&self) {
+        HOOKS.with(|h| h.borrow_mut().push("dropped"));
+    }
+}
+
+#[we_builder(
+    <div we_props="Signal<bool>">
+        <Hooked we_field="hooked" we_if={props} we_element />
+    </div>
+)]
+#[derive(Debug, Clone, WebElement)]
+struct HookedToggle {}
+
+#[test]
+fn test_ssr_lifecycle() {
+    let show = Signal::new(true);
+    let toggle = HookedToggle::build_with(show.clone()).unwrap();
+    let hooked = toggle.hooked.get().unwrap();
+    let count = Signal::new(0);
+    hooked.count.bind_text(&count);
+    assert_eq!(hooks(), vec!["init"]);
+
+    let body = document().unwrap().body().unwrap();
+    body.append(&toggle).unwrap();
+    assert_eq!(hooks(), vec!["mounted"]);
+
+    count.set(1);
+    assert_eq!(hooked.count.to_html(), "<span>1</span>");
+    assert_eq!(hooks(), vec!["updated"]);
+
+    show.set(false);
+    assert_eq!(hooks(), vec!["before_unmount", "unmounted"]);
+    show.set(true);
+    assert_eq!(hooks(), vec!["mounted"]);
+
+    // the hooks keep a handle without the root, the last handle is the one of the conditional
+    drop(hooked);
+    assert!(hooks().is_empty());
+    drop(toggle);
+    assert_eq!(hooks(), vec!["dropped"]);
+}
+
+#[we_builder(
+    <div we_props="u32">
+        <span we_field="count">{props}</span>
+    </div>
+)]
+// the hooks are called on an unbound handle, the struct doesn't need to be `Clone`
+#[derive(Debug, WebElement)]
+#[we(
+    init = "Self::setup",
+    mounted = "Self::mount",
+    drop = "Self::drop_count"
+)]
+struct DeriveHooks {}
+
+impl DeriveHooks {
+    fn setup(&mut self) -> webelements::Result<()> {
+        self.count.add_class("ready");
+        Ok(())
+    }
+
+    fn mount(&self) {
+        HOOKS.with(|h| h.borrow_mut().push("mounted"));
+    }
+
+    fn drop_count(&self) {
+        HOOKS.with(|h| h.borrow_mut().push("dropped"));
+    }
+}
+
+#[test]
+fn test_ssr_derive_hooks() {
+    let hooks_element = DeriveHooks::build_with(3).unwrap();
+    assert_eq!(
+        hooks_element.to_html(),
+        r#"<div><span class="ready">3</span></div>"#
+    );
+    document()
+        .unwrap()
+        .body()
+        .unwrap()
+        .append(&hooks_element)
+        .unwrap();
+    assert_eq!(hooks(), vec!["mounted"]);
+    drop(hooks_element);
+    assert_eq!(hooks(), vec!["dropped"]);
+}
+
+#[we_builder(
+    <p we_props="Signal<u32>">count: <we_text we_field="count" we_bind:text={props}>0</we_text> items</p>
+)]
+#[derive(Debug, WebElement)]
+#[we(updated = "Self::changed")]
+struct BoundText {}
+
+impl BoundText {
+    fn changed(&self) {
+        HOOKS.with(|h| h.borrow_mut().push("updated"));
+    }
+}
+
+#[test]
+fn test_ssr_text_updated() {
+    let count = Signal::new(1);
+    let el = BoundText::build_with(count.clone()).unwrap();
+    assert_eq!(el.to_html(), "<p>count: 1 items</p>");
+    assert!(hooks().is_empty());
+    count.set(2);
+    assert_eq!(el.count.text(), "2");
+    assert_eq!(hooks(), vec!["updated"]);
+
+    // the rendered text is merged
+    let p = webelements::ssr::Node::element("p", None);
+    p.append_child(&webelements::ssr::Node::text("count: 2 items"))
+        .unwrap();
+    let hydrated = BoundText::hydrate_with(&p, count.clone()).unwrap();
+    drop(el);
+    count.set(3);
+    assert_eq!(hydrated.to_html(), "<p>count: 3 items</p>");
+    assert_eq!(hooks(), vec!["updated"]);
+}
+
+#[test]
+fn test_ssr_node_insert() {
+    use webelements::ssr::Node;
+    let parent = Node::element("ul", None);
+    let (a, b) = (Node::element("li", None), Node::element("li", None));
+    parent.append_child(&a).unwrap();
+
+    // a missing reference child leaves the node where it was
+    let err = parent.insert_before(&a, Some(&b)).unwrap_err();
+    assert!(matches!(err, Error::NotFound));
+    assert_eq!(a.parent_node().as_ref(), Some(&parent));
+    parent.insert_before(&b, Some(&a)).unwrap();
+    assert_eq!(parent.children(), vec![b.clone(), a.clone()]);
+
+    // a node can't be put inside itself
+    let hierarchy_error = |err: Error| match err {
+        Error::JsError(JsError { name, .. }) => name.as_deref() == Some("HierarchyRequestError"),
+        _ => false,
+    };
+    assert!(hierarchy_error(a.append_child(&parent).unwrap_err()));
+    assert!(hierarchy_error(a.append_child(&a).unwrap_err()));
+    assert!(hierarchy_error(
+        parent.insert_before(&parent, Some(&a)).unwrap_err()
+    ));
+    assert_eq!(parent.children(), vec![b, a]);
+}
+
+#[test]
+fn test_ssr_tree() {
+    let list = Element::<elem::Ul>::new().unwrap();
+    let item = |text: &str| {
+        let item = Element::<elem::Li>::new().unwrap();
+        item.set_text(text);
+        item
+    };
+    let (b, d) = (item("b"), item("d"));
+    list.append(&b).unwrap();
+    list.prepend(item("a")).unwrap();
+    list.insert_after(&d, &b).unwrap();
+    list.insert_before(item("c"), &d).unwrap();
+    list.insert_after(item("e"), &d).unwrap();
+    assert_eq!(
+        list.children()
+            .map(|c| c.dom_element().text_content())
+            .collect::<Vec<_>>(),
+        vec!["a", "b", "c", "d", "e"]
+    );
+
+    d.replace_with(item("D")).unwrap();
+    list.remove_child(&b).unwrap();
+    let err = list.remove_child(&b).unwrap_err();
+    assert!(matches!(err.root_cause(), Error::NotFound));
+    assert_eq!(
+        err.to_string(),
+        "removing `<li>` from `<ul>`: a node that should be in the dom is not there"
+    );
+    list.children().next().unwrap().remove().unwrap();
+    assert_eq!(list.to_html(), "<ul><li>c</li><li>D</li><li>e</li></ul>");
+    list.append(Element::<elem::Span>::new().unwrap()).unwrap();
+    assert_eq!(list.children().count(), 4);
+    assert_eq!(list.children_as::<elem::Li>().count(), 3);
+    // an svg child is not an html element, like in the browser
+    let svg = webelements::ssr::Node::element("svg", Some("http://www.w3.org/2000/svg"));
+    list.dom_element().append_child(&svg).unwrap();
+    assert_eq!(list.dom_element().children().len(), 5);
+    assert_eq!(list.children().count(), 4);
+    list.clear_children().unwrap();
+    assert_eq!(list.to_html(), "<ul></ul>");
+
+    // removing a mounted component calls its unmount hooks
+    let hooked = Hooked::build().unwrap();
+    let body = document().unwrap().body().unwrap();
+    body.append(&list).unwrap();
+    list.append(&hooked).unwrap();
+    assert_eq!(hooks(), vec!["init", "mounted"]);
+    list.remove().unwrap();
+    assert_eq!(hooks(), vec!["before_unmount", "unmounted"]);
+    body.prepend(&list).unwrap();
+    assert_eq!(hooks(), vec!["mounted"]);
+    list.clear_children().unwrap();
+    assert_eq!(hooks(), vec!["before_unmount", "unmounted"]);
+
+    // only the replaced and the new element are checked
+    list.append(&hooked).unwrap();
+    let other = Hooked::build().unwrap();
+    hooked.replace_with(&other).unwrap();
+    assert_eq!(
+        hooks(),
+        vec!["mounted", "init", "before_unmount", "unmounted", "mounted"]
+    );
+}
+
+#[test]
+fn test_ssr_any_element() {
+    let count = Signal::new(1);
+    let span = Element::<elem::Span>::new().unwrap();
+    span.bind_text(&count);
+    let items: Vec<AnyElement> = vec![
+        Element::<elem::Div>::new().unwrap().into(),
+        span.into(),
+        Element::<elem::svg::Svg>::new().unwrap().into(),
+    ];
+    assert_eq!(
+        items.iter().map(AnyElement::tag).collect::<Vec<_>>(),
+        vec!["div", "span", "svg"]
+    );
+    assert!(items[0].is::<elem::Div>() && items[0].is::<elem::Base>());
+    assert!(!items[2].is::<elem::Base>());
+
+    let err = items[0]
+        .clone()
+        .try_into_typed::<elem::Input>()
+        .unwrap_err();
+    assert!(matches!(err.root_cause(), Error::Cast(_)));
+    assert!(err
+        .to_string()
+        .starts_with("casting `<div>` to `<input>`: "));
+
+    // the bindings are kept by the untyped element
+    let span = items[1].clone().try_into_typed::<elem::Span>().unwrap();
+    drop(items);
+    count.set(2);
+    assert_eq!(span.to_html(), "<span>2</span>");
+}
+
+#[test]
+fn test_ssr_query() {
+    let el = OtherElement::build().unwrap();
+    el.input.set_attr("data-name", "a \"b\"").unwrap();
+    let document = document().unwrap();
+    document.body().unwrap().append(&el).unwrap();
+
+    // a component can be hydrated from markup found in the document
+    let shell = document.by_id::<elem::Div>("other").unwrap().unwrap();
+    let hydrated = OtherElement::hydrate(shell.dom_element()).unwrap();
+    assert_eq!(hydrated.input.dom_element(), el.input.dom_element());
+
+    let repeated = document
+        .query_all::<elem::Div>("div.my-element > .repeated")
+        .unwrap();
+    assert_eq!(repeated.len(), 2);
+    assert_eq!(repeated[1].dom_element(), el.elem.repeated[1].dom_element());
+    assert!(document
+        .query_all::<elem::Div>("p > .repeated")
+        .unwrap()
+        .is_empty());
+    let input = el
+        .query::<elem::Input>(r#"input[type=text][data-name="a \"b\""]"#)
+        .unwrap()
+        .unwrap();
+    assert_eq!(input.dom_element(), el.input.dom_element());
+    assert_eq!(
+        el.query_all::<elem::Base>("#other p, [attr]")
+            .unwrap()
+            .len(),
+        2
+    );
+    // the subtree does not include the element itself
+    assert!(el.by_id::<elem::Div>("other").unwrap().is_none());
+    assert!(el.query::<elem::Div>("span").unwrap().is_none());
+
+    let err = el.query::<elem::Input>("p").unwrap_err();
+    assert!(matches!(err.root_cause(), Error::Cast(_)));
+    assert!(err
+        .to_string()
+        .starts_with("querying `p`: casting `<p>` to `<input>`: "));
+    match el
+        .query::<elem::Div>("div:first-child")
+        .unwrap_err()
+        .root_cause()
+    {
+        Error::JsError(JsError { name, .. }) => assert_eq!(name.as_deref(), Some("SyntaxError")),
+        err => panic!("unexpected error {:?}", err),
+    }
+}
@@ -1,11 +1,19 @@
-use webelements::{we_builder, WebElement, WebElementBuilder};
+#![cfg(not(feature = "ssr"))]
+
+use std::{cell::Cell, rc::Rc};
+
+use wasm_bindgen::JsCast;
+use webelements::{
+    elem, event, we_builder, AnyElement, Element, ElementList, Error, JsError, ListenerOptions,
+    Signal, WebElement, WebElementBuilder,
+};
 
 use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
 
 wasm_bindgen_test_configure!(run_in_browser);
 
 #[we_builder(
-    <div class="my-element" attr="value">
+    <div class="my-element" attr="value" we_unchecked="attr">
         <div class="repeated" we_field="repeated" we_repeat=5 />
     </div>
 )]
@@ -13,7 +21,7 @@ wasm_bindgen_test_configure!(run_in_browser);
 struct MyElement {}
 
 #[we_builder(
-    <div class="my-element" attr="value">
+    <div class="my-element" attr="value" we_unchecked="attr">
         <MyElement we_field="elem" we_repeat=2 we_element />
     </div>
 )]
@@ -25,3 +33,213 @@ fn test_we_elements() {
     let el = OtherElement::build().unwrap();
     assert_eq!(el.elem.first().unwrap().repeated.len(), 5)
 }
+
+#[we_builder(
+    <form class="list-form">
+        <label for="items">Items</label>
+        <ul we_field="list">
+            <li we_field="items" we_repeat=3><a href="#">item</a></li>
+        </ul>
+        <table><tbody><tr><td>cell</td></tr></tbody></table>
+        <select we_field="select"><option value="a">a</option></select>
+        <textarea we_field="text" />
+        <canvas we_field="canvas" />
+    </form>
+)]
+#[derive(Debug, Clone, WebElement)]
+struct AllElements {}
+
+#[wasm_bindgen_test]
+fn test_we_all_elements() {
+    let el = AllElements::build().unwrap();
+    assert_eq!(el.items.len(), 3);
+    assert_eq!(el.canvas.attr("class"), None);
+}
+
+#[we_builder(
+    <div class="chart">
+        <svg we_field="svg" viewBox="0 0 10 10">
+            <g class="series">
+                <path we_field="path" d="M 0 0 L 10 10" />
+                <circle we_field="points" we_repeat=2 r="1" />
+            </g>
+            <text>label</text>
+        </svg>
+        <math><mrow><mi>x</mi></mrow></math>
+    </div>
+)]
+#[derive(Debug, Clone, WebElement)]
+struct ChartElement {}
+
+#[wasm_bindgen_test]
+fn test_we_namespaces() {
+    let el = ChartElement::build().unwrap();
+    assert_eq!(el.points.len(), 2);
+    assert_eq!(el.path.attr("d").as_deref(), Some("M 0 0 L 10 10"));
+}
+
+#[wasm_bindgen_test]
+fn test_we_hydrate() {
+    let el = OtherElement::build().unwrap();
+    let hydrated = OtherElement::hydrate(el.dom_element()).unwrap();
+    assert_eq!(hydrated.elem.len(), 2);
+    assert!(MyElement::hydrate(hydrated.elem[0].repeated[0].dom_element()).is_err());
+}
+
+#[we_builder(
+    <div>
+        <button we_field="button" we_on:click="Self::clicked">click</button>
+        <span we_repeat=2 we_on:click={|this: &ClickElement, _e: web_sys::MouseEvent| this.add_class("span")} />
+    </div>
+)]
+#[derive(Debug, Clone, WebElement)]
+struct ClickElement {}
+
+impl ClickElement {
+    fn clicked(&self, _e: web_sys::MouseEvent) {
+        self.button.add_class("clicked");
+    }
+}
+
+#[wasm_bindgen_test]
+fn test_we_on_event() {
+    let el = ClickElement::build().unwrap();
+    let button: &web_sys::HtmlElement = el.button.dom_element().unchecked_ref();
+    button.click();
+    assert!(el.button.has_class("clicked"));
+}
+
+#[wasm_bindgen_test]
+fn test_we_listener_handle() {
+    let el = ClickElement::build().unwrap();
+    let button: &web_sys::HtmlElement = el.button.dom_element().unchecked_ref();
+    let count = Rc::new(Cell::new(0));
+    let counter = count.clone();
+    let handle = el
+        .button
+        .on::<event::Click>(move |_| counter.set(counter.get() + 1))
+        .unwrap();
+    let counter = count.clone();
+    el.button
+        .on_with::<event::Click>(ListenerOptions::default().once(), move |_| {
+            counter.set(counter.get() + 10)
+        })
+        .unwrap()
+        .forget();
+    button.click();
+    button.click();
+    assert_eq!(count.get(), 12);
+    drop(handle);
+    button.click();
+    assert_eq!(count.get(), 12);
+}
+
+#[wasm_bindgen_test]
+fn test_we_bind_signal() {
+    let el = MyElement::build().unwrap();
+    let active = Signal::new(false);
+    el.bind_class("active", &active);
+    el.bind_attr("data-active", &active).unwrap();
+    assert!(!el.has_class("active"));
+    active.set(true);
+    assert!(el.has_class("active"));
+    assert_eq!(el.attr("data-active").as_deref(), Some("true"));
+}
+
+#[wasm_bindgen_test]
+fn test_we_element_list() {
+    let el = MyElement::build().unwrap();
+    let list = ElementList::new(
+        &el.root,
+        |n: &u32| *n,
+        |n| {
+            let item = Element::<elem::Span>::new()?;
+            item.set_text(n.to_string());
+            Ok(item)
+        },
+    )
+    .unwrap();
+    list.set_items(&[1, 2, 3]).unwrap();
+    list.set_items(&[3, 1]).unwrap();
+    assert_eq!(list.keys(), vec![3, 1]);
+    assert_eq!(el.dom_element().children().length(), 7);
+}
+
+#[wasm_bindgen_test]
+fn test_we_js_error() {
+    let el = Element::<elem::Div>::new().unwrap();
+    let err = el.set_attr("bad name", "value").unwrap_err();
+    match err.root_cause() {
+        Error::JsError(JsError { name, stack, .. }) => {
+            assert_eq!(name.as_deref(), Some("InvalidCharacterError"));
+            assert!(stack.is_some());
+        }
+        err => panic!("unexpected error {:?}", err),
+    }
+    assert!(err
+        .to_string()
+        .starts_with("setting attribute `bad name` on `<div>`: InvalidCharacterError: "));
+}
+
+#[wasm_bindgen_test]
+fn test_we_drop_releases_listeners() {
+    let parent = Element::<elem::Div>::new().unwrap();
+    let el = ClickElement::build().unwrap();
+    parent.append(&el).unwrap();
+    let button: web_sys::HtmlElement = el.button.dom_element().clone().unchecked_into();
+    let count = Rc::new(Cell::new(0));
+    let counter = count.clone();
+    el.button
+        .on_click(move |_| counter.set(counter.get() + 1))
+        .unwrap();
+    button.click();
+    assert_eq!(count.get(), 1);
+    // the listeners live as long as the element, not as long as it is in the dom
+    parent.remove_child(&el).unwrap();
+    button.click();
+    assert_eq!(count.get(), 2);
+    assert_eq!(parent.children().count(), 0);
+    drop(el);
+    button.click();
+    assert_eq!(count.get(), 2);
+}
+
+#[wasm_bindgen_test]
+fn test_we_any_element() {
+    let input = Element::<elem::Input>::new().unwrap();
+    let any = AnyElement::from_dom_element(input.dom_element().clone());
+    assert_eq!(any.tag(), "input");
+    assert!(any.is::<elem::Base>());
+    assert!(matches!(
+        any.clone()
+            .try_into_typed::<elem::Div>()
+            .unwrap_err()
+            .root_cause(),
+        Error::Cast(_)
+    ));
+    let input = any.try_into_typed::<elem::Input>().unwrap();
+    input.set_value("1");
+    assert_eq!(input.value(), "1");
+}
+
+#[wasm_bindgen_test]
+fn test_we_query() {
+    let el = ClickElement::build().unwrap();
+    el.button.set_attr("id", "query-button").unwrap();
+    let document = webelements::document().unwrap();
+    document.body().unwrap().append(&el).unwrap();
+    let button = document
+        .by_id::<elem::Button>("query-button")
+        .unwrap()
+        .unwrap();
+    assert_eq!(button.dom_element(), el.button.dom_element());
+    assert_eq!(el.query_all::<elem::Button>("button").unwrap().len(), 1);
+    assert!(matches!(
+        document
+            .query::<elem::Div>("#query-button")
+            .unwrap_err()
+            .root_cause(),
+        Error::Cast(_)
+    ));
+    el.remove().unwrap();
+}